@@ -20,6 +20,76 @@ pub struct Config {
 
     /// Overlay color in hex (e.g., "#2D5A27" for green)
     pub overlay_color: String,
+
+    /// Whether the mouse/trackpad should also be blocked while locked, in
+    /// addition to the keyboard. Lets users choose keyboard-only or keyboard
+    /// and mouse; the keyboard is always blocked while locked, so there is
+    /// no mouse-only mode.
+    #[serde(default = "default_true")]
+    pub lock_mouse: bool,
+
+    /// When true, only block touch-originated pointer events - movement,
+    /// clicks and wheel alike (precision touchpads reporting through the
+    /// Windows pen/touch synthesis path) - and leave a traditional external
+    /// mouse usable.
+    /// The low-level mouse hook can't see the originating device directly,
+    /// so this is a best-effort heuristic based on `dwExtraInfo`'s touch
+    /// tag; a real per-device allowlist needs Raw Input.
+    #[serde(default)]
+    pub block_touchpad_only: bool,
+
+    /// Match the unlock hotkey by physical key position (scancode) rather
+    /// than by virtual key, so the same physical key unlocks regardless of
+    /// the active keyboard layout (AZERTY, Dvorak, language switches, etc).
+    #[serde(default)]
+    pub hotkey_match_physical: bool,
+
+    /// Automatically lock after this many seconds of no genuine keyboard or
+    /// mouse input. `None` disables auto-lock entirely.
+    #[serde(default)]
+    pub auto_lock_seconds: Option<u32>,
+
+    /// Raw Input device identifiers (the `GetRawInputDeviceInfo` device
+    /// name/path, which encodes a USB VID/PID for most hardware) that stay
+    /// usable while locked - e.g. a parent's own keyboard, or a hardware
+    /// security key that needs to keep typing OTPs. Empty means every
+    /// device is blocked while locked, same as before this feature existed.
+    #[serde(default)]
+    pub allowed_device_ids: Vec<String>,
+
+    /// Per-application overrides, applied on top of these defaults while
+    /// the matching executable is the foreground window. See `effective_for`.
+    #[serde(default)]
+    pub profiles: Vec<AppProfile>,
+
+    /// Custom overlay colors chosen via the settings dialog's `ChooseColorW`
+    /// picker, most-recent first, capped at 16 so the color combo's
+    /// "recent" section stays a single dropdown page. Colors picked from
+    /// `COLOR_PRESETS` never end up here.
+    #[serde(default)]
+    pub recent_colors: Vec<String>,
+}
+
+/// Overrides that apply only while a specific executable is the foreground
+/// window, e.g. higher opacity during a video call or notifications off
+/// while gaming. Any field left `None` falls back to the global `Config`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AppProfile {
+    /// Executable file name the profile matches, e.g. "Teams.exe". Matched
+    /// case-insensitively against the foreground window's process image name.
+    pub executable: String,
+    #[serde(default)]
+    pub hotkey: Option<String>,
+    #[serde(default)]
+    pub opacity: Option<f32>,
+    #[serde(default)]
+    pub overlay_color: Option<String>,
+    #[serde(default)]
+    pub notifications_enabled: Option<bool>,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 impl Default for Config {
@@ -30,6 +100,13 @@ impl Default for Config {
             notifications_enabled: true,
             // Colorblind-friendly green that's distinguishable
             overlay_color: "#1B5E20".to_string(),
+            lock_mouse: true,
+            block_touchpad_only: false,
+            hotkey_match_physical: false,
+            auto_lock_seconds: None,
+            allowed_device_ids: Vec::new(),
+            profiles: Vec::new(),
+            recent_colors: Vec::new(),
         }
     }
 }
@@ -73,77 +150,622 @@ impl Config {
 
     /// Parse overlay color from hex string to RGB
     pub fn parse_overlay_color(&self) -> (u8, u8, u8) {
-        let hex = self.overlay_color.trim_start_matches('#');
-        if hex.len() == 6 {
-            if let (Ok(r), Ok(g), Ok(b)) = (
-                u8::from_str_radix(&hex[0..2], 16),
-                u8::from_str_radix(&hex[2..4], 16),
-                u8::from_str_radix(&hex[4..6], 16),
-            ) {
-                return (r, g, b);
+        parse_hex_color(&self.overlay_color)
+    }
+
+    /// Resolve the effective config while `executable` (a process image
+    /// file name, e.g. "Teams.exe") is the foreground window: the matching
+    /// profile's overrides applied on top of these global defaults, or a
+    /// plain clone of `self` if no profile matches.
+    pub fn effective_for(&self, executable: &str) -> Config {
+        let mut effective = self.clone();
+        if let Some(profile) = self
+            .profiles
+            .iter()
+            .find(|p| p.executable.eq_ignore_ascii_case(executable))
+        {
+            if let Some(ref hotkey) = profile.hotkey {
+                effective.hotkey = hotkey.clone();
+            }
+            if let Some(opacity) = profile.opacity {
+                effective.opacity = opacity;
+            }
+            if let Some(ref color) = profile.overlay_color {
+                effective.overlay_color = color.clone();
+            }
+            if let Some(notifications_enabled) = profile.notifications_enabled {
+                effective.notifications_enabled = notifications_enabled;
             }
         }
-        // Default to dark green if parsing fails
-        (27, 94, 32)
+        effective
+    }
+
+    /// Record `hex` as the most recently used custom overlay color: move it
+    /// to the front of `recent_colors` if already present, otherwise insert
+    /// it, then truncate to the 16 entries the color combo has room to show.
+    pub fn push_recent_color(&mut self, hex: &str) {
+        self.recent_colors.retain(|c| !c.eq_ignore_ascii_case(hex));
+        self.recent_colors.insert(0, hex.to_string());
+        self.recent_colors.truncate(16);
     }
 }
 
-/// Parse hotkey string into modifier flags and virtual key code
-/// Returns (modifiers, vk_code) where modifiers is a bitmask
-pub fn parse_hotkey(hotkey: &str) -> Option<(u32, u32)> {
-    use windows::Win32::UI::Input::KeyboardAndMouse::*;
-
-    let parts: Vec<&str> = hotkey.to_lowercase().split('+').map(|s| s.trim()).collect();
-
-    let mut modifiers: u32 = 0;
-    let mut vk_code: Option<u32> = None;
-
-    for part in parts {
-        match part {
-            "ctrl" | "control" => modifiers |= MOD_CONTROL.0,
-            "alt" => modifiers |= MOD_ALT.0,
-            "shift" => modifiers |= MOD_SHIFT.0,
-            "win" | "windows" => modifiers |= MOD_WIN.0,
-            // Single letter keys
-            key if key.len() == 1 => {
-                let c = key.chars().next().unwrap().to_ascii_uppercase();
-                if c.is_ascii_alphabetic() {
-                    vk_code = Some(c as u32);
-                } else if c.is_ascii_digit() {
-                    vk_code = Some(c as u32);
+/// Parse a `#RRGGBB` hex color string into RGB components, defaulting to
+/// dark green if `hex` isn't a well-formed 6-digit color.
+pub fn parse_hex_color(hex: &str) -> (u8, u8, u8) {
+    let hex = hex.trim_start_matches('#');
+    if hex.len() == 6 {
+        if let (Ok(r), Ok(g), Ok(b)) = (
+            u8::from_str_radix(&hex[0..2], 16),
+            u8::from_str_radix(&hex[2..4], 16),
+            u8::from_str_radix(&hex[4..6], 16),
+        ) {
+            return (r, g, b);
+        }
+    }
+    // Default to dark green if parsing fails
+    (27, 94, 32)
+}
+
+/// A single non-modifier key recognized in a hotkey string
+///
+/// Covers letters, digits, function keys, navigation/editing keys, the
+/// punctuation/OEM row, and the numpad, each mapped to its Windows virtual
+/// key code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Letter(u8),  // 0-25, A-Z
+    Digit(u8),   // 0-9
+    Function(u8), // 1-24
+    Space,
+    Enter,
+    Escape,
+    Tab,
+    Backspace,
+    Delete,
+    Insert,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Up,
+    Down,
+    Left,
+    Right,
+    NumLock,
+    ScrollLock,
+    Pause,
+    PrintScreen,
+    // Punctuation / OEM row
+    Semicolon,
+    Equals,
+    Comma,
+    Minus,
+    Period,
+    Slash,
+    Backquote,
+    LBracket,
+    Backslash,
+    RBracket,
+    Quote,
+    // Numpad
+    Numpad(u8), // 0-9
+    NumpadMultiply,
+    NumpadAdd,
+    NumpadSeparator,
+    NumpadSubtract,
+    NumpadDecimal,
+    NumpadDivide,
+    // Media keys
+    MediaPlayPause,
+    MediaStop,
+    MediaNextTrack,
+    MediaPrevTrack,
+    VolumeUp,
+    VolumeDown,
+    VolumeMute,
+}
+
+impl Key {
+    /// Resolve the Windows virtual-key code for this key
+    pub fn to_vk(self) -> u32 {
+        use windows::Win32::UI::Input::KeyboardAndMouse::*;
+
+        match self {
+            Key::Letter(n) => b'A' as u32 + n as u32,
+            Key::Digit(n) => b'0' as u32 + n as u32,
+            Key::Function(n) => VK_F1.0 as u32 + (n as u32 - 1),
+            Key::Space => VK_SPACE.0 as u32,
+            Key::Enter => VK_RETURN.0 as u32,
+            Key::Escape => VK_ESCAPE.0 as u32,
+            Key::Tab => VK_TAB.0 as u32,
+            Key::Backspace => VK_BACK.0 as u32,
+            Key::Delete => VK_DELETE.0 as u32,
+            Key::Insert => VK_INSERT.0 as u32,
+            Key::Home => VK_HOME.0 as u32,
+            Key::End => VK_END.0 as u32,
+            Key::PageUp => VK_PRIOR.0 as u32,
+            Key::PageDown => VK_NEXT.0 as u32,
+            Key::Up => VK_UP.0 as u32,
+            Key::Down => VK_DOWN.0 as u32,
+            Key::Left => VK_LEFT.0 as u32,
+            Key::Right => VK_RIGHT.0 as u32,
+            Key::NumLock => VK_NUMLOCK.0 as u32,
+            Key::ScrollLock => VK_SCROLL.0 as u32,
+            Key::Pause => VK_PAUSE.0 as u32,
+            Key::PrintScreen => VK_SNAPSHOT.0 as u32,
+            Key::Semicolon => VK_OEM_1.0 as u32,
+            Key::Equals => VK_OEM_PLUS.0 as u32,
+            Key::Comma => VK_OEM_COMMA.0 as u32,
+            Key::Minus => VK_OEM_MINUS.0 as u32,
+            Key::Period => VK_OEM_PERIOD.0 as u32,
+            Key::Slash => VK_OEM_2.0 as u32,
+            Key::Backquote => VK_OEM_3.0 as u32,
+            Key::LBracket => VK_OEM_4.0 as u32,
+            Key::Backslash => VK_OEM_5.0 as u32,
+            Key::RBracket => VK_OEM_6.0 as u32,
+            Key::Quote => VK_OEM_7.0 as u32,
+            Key::Numpad(n) => VK_NUMPAD0.0 as u32 + n as u32,
+            Key::NumpadMultiply => VK_MULTIPLY.0 as u32,
+            Key::NumpadAdd => VK_ADD.0 as u32,
+            Key::NumpadSeparator => VK_SEPARATOR.0 as u32,
+            Key::NumpadSubtract => VK_SUBTRACT.0 as u32,
+            Key::NumpadDecimal => VK_DECIMAL.0 as u32,
+            Key::NumpadDivide => VK_DIVIDE.0 as u32,
+            Key::MediaPlayPause => VK_MEDIA_PLAY_PAUSE.0 as u32,
+            Key::MediaStop => VK_MEDIA_STOP.0 as u32,
+            Key::MediaNextTrack => VK_MEDIA_NEXT_TRACK.0 as u32,
+            Key::MediaPrevTrack => VK_MEDIA_PREV_TRACK.0 as u32,
+            Key::VolumeUp => VK_VOLUME_UP.0 as u32,
+            Key::VolumeDown => VK_VOLUME_DOWN.0 as u32,
+            Key::VolumeMute => VK_VOLUME_MUTE.0 as u32,
+        }
+    }
+
+    /// The inverse of `to_vk`: resolve a `Key` from a Windows virtual-key
+    /// code, e.g. to decode the `msctls_hotkey32` control's `HKM_GETHOTKEY`
+    /// result or to format a raw vk for display.
+    pub fn from_vk(vk: u32) -> Option<Key> {
+        // Every Key variant's vk is distinct, so a linear scan over the
+        // handful of named keys plus the contiguous letter/digit/function/
+        // numpad ranges round-trips to_vk exactly.
+        if (b'A' as u32..=b'Z' as u32).contains(&vk) {
+            return Some(Key::Letter((vk - b'A' as u32) as u8));
+        }
+        if (b'0' as u32..=b'9' as u32).contains(&vk) {
+            return Some(Key::Digit((vk - b'0' as u32) as u8));
+        }
+
+        use windows::Win32::UI::Input::KeyboardAndMouse::*;
+        if (VK_F1.0 as u32..=(VK_F1.0 as u32 + 23)).contains(&vk) {
+            return Some(Key::Function((vk - VK_F1.0 as u32 + 1) as u8));
+        }
+        if (VK_NUMPAD0.0 as u32..=VK_NUMPAD9.0 as u32).contains(&vk) {
+            return Some(Key::Numpad((vk - VK_NUMPAD0.0 as u32) as u8));
+        }
+
+        Some(match vk {
+            v if v == VK_SPACE.0 as u32 => Key::Space,
+            v if v == VK_RETURN.0 as u32 => Key::Enter,
+            v if v == VK_ESCAPE.0 as u32 => Key::Escape,
+            v if v == VK_TAB.0 as u32 => Key::Tab,
+            v if v == VK_BACK.0 as u32 => Key::Backspace,
+            v if v == VK_DELETE.0 as u32 => Key::Delete,
+            v if v == VK_INSERT.0 as u32 => Key::Insert,
+            v if v == VK_HOME.0 as u32 => Key::Home,
+            v if v == VK_END.0 as u32 => Key::End,
+            v if v == VK_PRIOR.0 as u32 => Key::PageUp,
+            v if v == VK_NEXT.0 as u32 => Key::PageDown,
+            v if v == VK_UP.0 as u32 => Key::Up,
+            v if v == VK_DOWN.0 as u32 => Key::Down,
+            v if v == VK_LEFT.0 as u32 => Key::Left,
+            v if v == VK_RIGHT.0 as u32 => Key::Right,
+            v if v == VK_NUMLOCK.0 as u32 => Key::NumLock,
+            v if v == VK_SCROLL.0 as u32 => Key::ScrollLock,
+            v if v == VK_PAUSE.0 as u32 => Key::Pause,
+            v if v == VK_SNAPSHOT.0 as u32 => Key::PrintScreen,
+            v if v == VK_OEM_1.0 as u32 => Key::Semicolon,
+            v if v == VK_OEM_PLUS.0 as u32 => Key::Equals,
+            v if v == VK_OEM_COMMA.0 as u32 => Key::Comma,
+            v if v == VK_OEM_MINUS.0 as u32 => Key::Minus,
+            v if v == VK_OEM_PERIOD.0 as u32 => Key::Period,
+            v if v == VK_OEM_2.0 as u32 => Key::Slash,
+            v if v == VK_OEM_3.0 as u32 => Key::Backquote,
+            v if v == VK_OEM_4.0 as u32 => Key::LBracket,
+            v if v == VK_OEM_5.0 as u32 => Key::Backslash,
+            v if v == VK_OEM_6.0 as u32 => Key::RBracket,
+            v if v == VK_OEM_7.0 as u32 => Key::Quote,
+            v if v == VK_MULTIPLY.0 as u32 => Key::NumpadMultiply,
+            v if v == VK_ADD.0 as u32 => Key::NumpadAdd,
+            v if v == VK_SEPARATOR.0 as u32 => Key::NumpadSeparator,
+            v if v == VK_SUBTRACT.0 as u32 => Key::NumpadSubtract,
+            v if v == VK_DECIMAL.0 as u32 => Key::NumpadDecimal,
+            v if v == VK_DIVIDE.0 as u32 => Key::NumpadDivide,
+            v if v == VK_MEDIA_PLAY_PAUSE.0 as u32 => Key::MediaPlayPause,
+            v if v == VK_MEDIA_STOP.0 as u32 => Key::MediaStop,
+            v if v == VK_MEDIA_NEXT_TRACK.0 as u32 => Key::MediaNextTrack,
+            v if v == VK_MEDIA_PREV_TRACK.0 as u32 => Key::MediaPrevTrack,
+            v if v == VK_VOLUME_UP.0 as u32 => Key::VolumeUp,
+            v if v == VK_VOLUME_DOWN.0 as u32 => Key::VolumeDown,
+            v if v == VK_VOLUME_MUTE.0 as u32 => Key::VolumeMute,
+            _ => return None,
+        })
+    }
+
+    /// Canonical lowercase token used in config strings (round-trips through `FromStr`)
+    pub fn token(self) -> String {
+        match self {
+            Key::Letter(n) => ((b'a' + n) as char).to_string(),
+            Key::Digit(n) => ((b'0' + n) as char).to_string(),
+            Key::Function(n) => format!("f{n}"),
+            Key::Space => "space".to_string(),
+            Key::Enter => "enter".to_string(),
+            Key::Escape => "esc".to_string(),
+            Key::Tab => "tab".to_string(),
+            Key::Backspace => "backspace".to_string(),
+            Key::Delete => "delete".to_string(),
+            Key::Insert => "insert".to_string(),
+            Key::Home => "home".to_string(),
+            Key::End => "end".to_string(),
+            Key::PageUp => "pageup".to_string(),
+            Key::PageDown => "pagedown".to_string(),
+            Key::Up => "up".to_string(),
+            Key::Down => "down".to_string(),
+            Key::Left => "left".to_string(),
+            Key::Right => "right".to_string(),
+            Key::NumLock => "numlock".to_string(),
+            Key::ScrollLock => "scrolllock".to_string(),
+            Key::Pause => "pause".to_string(),
+            Key::PrintScreen => "printscreen".to_string(),
+            Key::Semicolon => ";".to_string(),
+            Key::Equals => "=".to_string(),
+            Key::Comma => ",".to_string(),
+            Key::Minus => "-".to_string(),
+            Key::Period => ".".to_string(),
+            Key::Slash => "/".to_string(),
+            Key::Backquote => "`".to_string(),
+            Key::LBracket => "[".to_string(),
+            Key::Backslash => "\\".to_string(),
+            Key::RBracket => "]".to_string(),
+            Key::Quote => "'".to_string(),
+            Key::Numpad(n) => format!("numpad{n}"),
+            Key::NumpadMultiply => "numpad*".to_string(),
+            // Not "numpad+": `Hotkey::from_str` splits tokens on '+' before
+            // `Key::parse` ever sees them, so a separator character can't
+            // appear in a token and still round-trip.
+            Key::NumpadAdd => "numpadadd".to_string(),
+            Key::NumpadSeparator => "numpadsep".to_string(),
+            Key::NumpadSubtract => "numpad-".to_string(),
+            Key::NumpadDecimal => "numpad.".to_string(),
+            Key::NumpadDivide => "numpad/".to_string(),
+            Key::MediaPlayPause => "mediaplaypause".to_string(),
+            Key::MediaStop => "mediastop".to_string(),
+            Key::MediaNextTrack => "medianext".to_string(),
+            Key::MediaPrevTrack => "mediaprev".to_string(),
+            Key::VolumeUp => "volumeup".to_string(),
+            Key::VolumeDown => "volumedown".to_string(),
+            Key::VolumeMute => "volumemute".to_string(),
+        }
+    }
+
+    fn parse(token: &str) -> Result<Key, HotkeyParseError> {
+        if token.len() == 1 {
+            let c = token.chars().next().unwrap();
+            if c.is_ascii_alphabetic() {
+                return Ok(Key::Letter(c.to_ascii_lowercase() as u8 - b'a'));
+            }
+            if c.is_ascii_digit() {
+                return Ok(Key::Digit(c as u8 - b'0'));
+            }
+        }
+
+        if let Some(rest) = token.strip_prefix('f') {
+            if !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit()) {
+                let num: u32 = rest.parse().map_err(|_| HotkeyParseError::UnknownToken(token.to_string()))?;
+                if !(1..=24).contains(&num) {
+                    return Err(HotkeyParseError::FunctionKeyOutOfRange(num));
+                }
+                return Ok(Key::Function(num as u8));
+            }
+        }
+
+        if let Some(rest) = token.strip_prefix("numpad") {
+            match rest {
+                "0" | "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9" => {
+                    return Ok(Key::Numpad(rest.parse().unwrap()));
                 }
+                "*" => return Ok(Key::NumpadMultiply),
+                "add" => return Ok(Key::NumpadAdd),
+                "sep" => return Ok(Key::NumpadSeparator),
+                "-" => return Ok(Key::NumpadSubtract),
+                "." => return Ok(Key::NumpadDecimal),
+                "/" => return Ok(Key::NumpadDivide),
+                _ => return Err(HotkeyParseError::UnknownToken(token.to_string())),
             }
-            // Function keys
-            key if key.starts_with('f') && key.len() <= 3 => {
-                if let Ok(num) = key[1..].parse::<u32>() {
-                    if num >= 1 && num <= 24 {
-                        vk_code = Some(VK_F1.0 as u32 + num - 1);
+        }
+
+        Ok(match token {
+            "space" => Key::Space,
+            "enter" | "return" => Key::Enter,
+            "escape" | "esc" => Key::Escape,
+            "tab" => Key::Tab,
+            "backspace" => Key::Backspace,
+            "delete" | "del" => Key::Delete,
+            "insert" | "ins" => Key::Insert,
+            "home" => Key::Home,
+            "end" => Key::End,
+            "pageup" | "pgup" => Key::PageUp,
+            "pagedown" | "pgdn" => Key::PageDown,
+            "up" => Key::Up,
+            "down" => Key::Down,
+            "left" => Key::Left,
+            "right" => Key::Right,
+            "numlock" => Key::NumLock,
+            "scrolllock" => Key::ScrollLock,
+            "pause" => Key::Pause,
+            "printscreen" | "prtsc" => Key::PrintScreen,
+            "mediaplaypause" | "playpause" => Key::MediaPlayPause,
+            "mediastop" => Key::MediaStop,
+            "medianext" | "medianexttrack" => Key::MediaNextTrack,
+            "mediaprev" | "mediaprevtrack" => Key::MediaPrevTrack,
+            "volumeup" => Key::VolumeUp,
+            "volumedown" => Key::VolumeDown,
+            "volumemute" | "mute" => Key::VolumeMute,
+            ";" => Key::Semicolon,
+            "=" => Key::Equals,
+            "," => Key::Comma,
+            "-" => Key::Minus,
+            "." => Key::Period,
+            "/" => Key::Slash,
+            "`" => Key::Backquote,
+            "[" => Key::LBracket,
+            "\\" => Key::Backslash,
+            "]" => Key::RBracket,
+            "'" => Key::Quote,
+            _ => return Err(HotkeyParseError::UnknownToken(token.to_string())),
+        })
+    }
+}
+
+impl std::fmt::Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.token())
+    }
+}
+
+/// Error produced when a hotkey string can't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HotkeyParseError {
+    /// A token didn't match any known modifier or key (e.g. `ctrl+banana`)
+    UnknownToken(String),
+    /// No non-modifier key was present (e.g. `ctrl+shift`)
+    MissingKey,
+    /// More than one non-modifier key was present (e.g. `ctrl+a+b`)
+    DuplicateKey,
+    /// A function key number was outside the supported `F1`-`F24` range
+    FunctionKeyOutOfRange(u32),
+}
+
+impl std::fmt::Display for HotkeyParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HotkeyParseError::UnknownToken(tok) => write!(f, "unrecognized key token: \"{tok}\""),
+            HotkeyParseError::MissingKey => write!(f, "hotkey has no non-modifier key"),
+            HotkeyParseError::DuplicateKey => write!(f, "hotkey specifies more than one non-modifier key"),
+            HotkeyParseError::FunctionKeyOutOfRange(n) => {
+                write!(f, "function key F{n} is out of range (supported: F1-F24)")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HotkeyParseError {}
+
+/// A parsed hotkey: modifier flags plus exactly one non-modifier key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hotkey {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub win: bool,
+    pub key: Key,
+}
+
+impl Hotkey {
+    /// Convert to the (modifiers bitmask, vk code) form used by the registration APIs
+    pub fn to_modifiers_and_vk(self) -> (u32, u32) {
+        use windows::Win32::UI::Input::KeyboardAndMouse::{MOD_ALT, MOD_CONTROL, MOD_SHIFT, MOD_WIN};
+
+        let mut modifiers = 0u32;
+        if self.ctrl {
+            modifiers |= MOD_CONTROL.0;
+        }
+        if self.alt {
+            modifiers |= MOD_ALT.0;
+        }
+        if self.shift {
+            modifiers |= MOD_SHIFT.0;
+        }
+        if self.win {
+            modifiers |= MOD_WIN.0;
+        }
+        (modifiers, self.key.to_vk())
+    }
+
+    /// The inverse of `to_modifiers_and_vk`, used to decode raw
+    /// modifiers/vk pairs (e.g. from the `msctls_hotkey32` control) back
+    /// into a `Hotkey`.
+    pub fn from_modifiers_and_vk(modifiers: u32, vk: u32) -> Option<Hotkey> {
+        use windows::Win32::UI::Input::KeyboardAndMouse::{MOD_ALT, MOD_CONTROL, MOD_SHIFT, MOD_WIN};
+
+        let key = Key::from_vk(vk)?;
+        Some(Hotkey {
+            ctrl: (modifiers & MOD_CONTROL.0) != 0,
+            alt: (modifiers & MOD_ALT.0) != 0,
+            shift: (modifiers & MOD_SHIFT.0) != 0,
+            win: (modifiers & MOD_WIN.0) != 0,
+            key,
+        })
+    }
+}
+
+impl std::str::FromStr for Hotkey {
+    type Err = HotkeyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ctrl = false;
+        let mut alt = false;
+        let mut shift = false;
+        let mut win = false;
+        let mut key: Option<Key> = None;
+
+        for part in s.to_lowercase().split('+').map(|p| p.trim().to_string()) {
+            match part.as_str() {
+                "ctrl" | "control" => ctrl = true,
+                "alt" => alt = true,
+                "shift" => shift = true,
+                "win" | "windows" | "super" => win = true,
+                "" => {}
+                token => {
+                    let parsed = Key::parse(token)?;
+                    if key.is_some() {
+                        return Err(HotkeyParseError::DuplicateKey);
                     }
+                    key = Some(parsed);
                 }
             }
-            // Special keys
-            "space" => vk_code = Some(VK_SPACE.0 as u32),
-            "enter" | "return" => vk_code = Some(VK_RETURN.0 as u32),
-            "escape" | "esc" => vk_code = Some(VK_ESCAPE.0 as u32),
-            "tab" => vk_code = Some(VK_TAB.0 as u32),
-            "backspace" => vk_code = Some(VK_BACK.0 as u32),
-            "delete" | "del" => vk_code = Some(VK_DELETE.0 as u32),
-            "insert" | "ins" => vk_code = Some(VK_INSERT.0 as u32),
-            "home" => vk_code = Some(VK_HOME.0 as u32),
-            "end" => vk_code = Some(VK_END.0 as u32),
-            "pageup" | "pgup" => vk_code = Some(VK_PRIOR.0 as u32),
-            "pagedown" | "pgdn" => vk_code = Some(VK_NEXT.0 as u32),
-            "up" => vk_code = Some(VK_UP.0 as u32),
-            "down" => vk_code = Some(VK_DOWN.0 as u32),
-            "left" => vk_code = Some(VK_LEFT.0 as u32),
-            "right" => vk_code = Some(VK_RIGHT.0 as u32),
-            "numlock" => vk_code = Some(VK_NUMLOCK.0 as u32),
-            "scrolllock" => vk_code = Some(VK_SCROLL.0 as u32),
-            "pause" => vk_code = Some(VK_PAUSE.0 as u32),
-            "printscreen" | "prtsc" => vk_code = Some(VK_SNAPSHOT.0 as u32),
-            _ => {}
-        }
-    }
-
-    vk_code.map(|vk| (modifiers, vk))
+        }
+
+        match key {
+            Some(key) => Ok(Hotkey { ctrl, alt, shift, win, key }),
+            None => Err(HotkeyParseError::MissingKey),
+        }
+    }
+}
+
+impl std::fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.win {
+            write!(f, "Win+")?;
+        }
+        match self.key {
+            Key::Letter(n) => write!(f, "{}", (b'A' + n) as char),
+            key => write!(f, "{}", key),
+        }
+    }
+}
+
+/// Parse hotkey string into modifier flags and virtual key code
+/// Returns (modifiers, vk_code) where modifiers is a bitmask
+pub fn parse_hotkey(hotkey: &str) -> Result<(u32, u32), HotkeyParseError> {
+    hotkey.parse::<Hotkey>().map(Hotkey::to_modifiers_and_vk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every `Key` variant, once each, so `to_vk`/`from_vk` and `token`
+    /// round-trip tests cover the whole enum instead of a hand-picked subset.
+    fn all_keys() -> Vec<Key> {
+        let mut keys = vec![
+            Key::Space,
+            Key::Enter,
+            Key::Escape,
+            Key::Tab,
+            Key::Backspace,
+            Key::Delete,
+            Key::Insert,
+            Key::Home,
+            Key::End,
+            Key::PageUp,
+            Key::PageDown,
+            Key::Up,
+            Key::Down,
+            Key::Left,
+            Key::Right,
+            Key::NumLock,
+            Key::ScrollLock,
+            Key::Pause,
+            Key::PrintScreen,
+            Key::Semicolon,
+            Key::Equals,
+            Key::Comma,
+            Key::Minus,
+            Key::Period,
+            Key::Slash,
+            Key::Backquote,
+            Key::LBracket,
+            Key::Backslash,
+            Key::RBracket,
+            Key::Quote,
+            Key::NumpadMultiply,
+            Key::NumpadAdd,
+            Key::NumpadSeparator,
+            Key::NumpadSubtract,
+            Key::NumpadDecimal,
+            Key::NumpadDivide,
+            Key::MediaPlayPause,
+            Key::MediaStop,
+            Key::MediaNextTrack,
+            Key::MediaPrevTrack,
+            Key::VolumeUp,
+            Key::VolumeDown,
+            Key::VolumeMute,
+        ];
+        keys.extend((0..26).map(Key::Letter));
+        keys.extend((0..10).map(Key::Digit));
+        keys.extend((1..=24).map(Key::Function));
+        keys.extend((0..10).map(Key::Numpad));
+        keys
+    }
+
+    #[test]
+    fn vk_round_trips_for_every_key() {
+        for key in all_keys() {
+            assert_eq!(Key::from_vk(key.to_vk()), Some(key), "vk round-trip failed for {key:?}");
+        }
+    }
+
+    #[test]
+    fn token_round_trips_for_every_key() {
+        for key in all_keys() {
+            assert_eq!(Key::parse(&key.token()).unwrap(), key, "token round-trip failed for {key:?}");
+        }
+    }
+
+    #[test]
+    fn hotkey_string_round_trips() {
+        let cases = [
+            "a",
+            "ctrl+b",
+            "ctrl+shift+l",
+            "alt+f4",
+            "win+l",
+            "ctrl+alt+delete",
+            "ctrl+numpadadd",
+            "shift+numpad5",
+            "ctrl+alt+shift+win+space",
+        ];
+        for case in cases {
+            let first: Hotkey = case.parse().unwrap();
+            let second: Hotkey = first.to_string().parse().unwrap();
+            assert_eq!(first, second, "hotkey round-trip failed for {case:?}");
+        }
+    }
+
+    #[test]
+    fn function_key_out_of_range_is_rejected() {
+        assert_eq!(Key::parse("f25"), Err(HotkeyParseError::FunctionKeyOutOfRange(25)));
+        assert!(Key::parse("f24").is_ok());
+    }
 }
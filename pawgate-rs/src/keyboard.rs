@@ -5,7 +5,7 @@
 
 use crate::config::{parse_hotkey, Config};
 use crate::AppState;
-use log::{debug, info};
+use log::{debug, error, info};
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
@@ -20,8 +20,48 @@ thread_local! {
 
 struct HookState {
     state: Arc<AppState>,
-    hotkey_modifiers: u32,
-    hotkey_vk: u32,
+    /// Virtual keys the hook has seen go down while locked (blocked keys and
+    /// the modifiers let through alike), so
+    /// we can synthesize the matching key-up on unlock and avoid leaving an
+    /// application thinking a key (especially a modifier) is still held.
+    held_keys: std::cell::RefCell<std::collections::HashSet<u32>>,
+}
+
+/// Parse `hotkey` and publish it (plus `match_physical`) to `state`'s shared
+/// atomics, where the hook proc reads them on every keydown. Called once at
+/// startup and again whenever the settings dialog saves a new hotkey, so a
+/// change takes effect for the very next keystroke without reinstalling the
+/// hook.
+pub fn reload_hotkey(state: &AppState, hotkey: &str, match_physical: bool) {
+    let (modifiers, vk) = match parse_hotkey(hotkey) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            error!("Invalid hotkey \"{}\": {} - keeping previous hotkey", hotkey, e);
+            return;
+        }
+    };
+
+    // Derive the scancode for the configured hotkey under the layout active
+    // right now, so physical-position matching stays correct for this
+    // session even if the user later switches layouts. MAPVK_VK_TO_VSC_EX
+    // (rather than MAPVK_VK_TO_VSC) reports extended keys with the 0xE0
+    // prefix in its high byte, which we fold into the same 0xE000 bit the
+    // hook proc ORs into the live scancode below, so both sides agree for
+    // arrows, Delete/Insert/Home/End/PageUp/Down, and numpad Enter.
+    let raw_scancode = unsafe { MapVirtualKeyW(vk, MAPVK_VK_TO_VSC_EX) };
+    let scancode = (raw_scancode & 0xFF) | if (raw_scancode >> 8) == 0xE0 { 0xE000 } else { 0 };
+
+    state.hotkey_modifiers.store(modifiers, Ordering::SeqCst);
+    state.hotkey_vk.store(vk, Ordering::SeqCst);
+    state.hotkey_scancode.store(scancode, Ordering::SeqCst);
+    state
+        .hotkey_match_physical
+        .store(match_physical, Ordering::SeqCst);
+
+    info!(
+        "Hotkey set to {} (modifiers={:#x}, vk={:#x}, scancode={:#x}, match_physical={})",
+        hotkey, modifiers, vk, scancode, match_physical
+    );
 }
 
 /// Virtual key codes for modifier keys
@@ -36,20 +76,22 @@ const VK_RWIN_U32: u32 = VK_RWIN.0 as u32;
 
 /// Run the keyboard hook message loop
 pub fn run_keyboard_hook(state: Arc<AppState>, config: Config) {
-    // Parse the hotkey configuration
-    let (modifiers, vk) = parse_hotkey(&config.hotkey).unwrap_or((MOD_CONTROL.0, 'B' as u32));
+    // Parse the hotkey configuration (main::load_config already validated this,
+    // so a failure here only happens if the config was edited on disk between
+    // load and hook startup; fall back to the documented default).
+    if parse_hotkey(&config.hotkey).is_ok() {
+        reload_hotkey(&state, &config.hotkey, config.hotkey_match_physical);
+    } else {
+        reload_hotkey(&state, "Ctrl+B", config.hotkey_match_physical);
+    }
 
-    info!(
-        "Keyboard hook starting with hotkey: {} (modifiers={:#x}, vk={:#x})",
-        config.hotkey, modifiers, vk
-    );
+    info!("Keyboard hook starting");
 
     // Store state in thread-local storage for the hook callback
     HOOK_STATE.with(|hs| {
         *hs.borrow_mut() = Some(HookState {
             state: Arc::clone(&state),
-            hotkey_modifiers: modifiers,
-            hotkey_vk: vk,
+            held_keys: std::cell::RefCell::new(std::collections::HashSet::new()),
         });
     });
 
@@ -146,6 +188,36 @@ fn is_modifier_vk(vk: u32) -> bool {
     )
 }
 
+/// Synthesize key-up events for every key the hook observed going down while
+/// locked, so no application is left believing a key (especially a modifier
+/// like Ctrl/Shift/Alt) is still held after unlock. Keys the OS no longer
+/// reports as physically down are skipped, since there's nothing to release.
+unsafe fn release_held_keys(held_keys: &std::cell::RefCell<std::collections::HashSet<u32>>) {
+    let keys: Vec<u32> = held_keys.borrow_mut().drain().collect();
+
+    for vk in keys {
+        let still_down = (GetAsyncKeyState(vk as i32) as u16 & 0x8000) != 0;
+        if !still_down {
+            continue;
+        }
+
+        debug!("Releasing possibly-stuck key on unlock: vk={:#x}", vk);
+        let input = INPUT {
+            r#type: INPUT_KEYBOARD,
+            Anonymous: INPUT_0 {
+                ki: KEYBDINPUT {
+                    wVk: VIRTUAL_KEY(vk as u16),
+                    wScan: 0,
+                    dwFlags: KEYEVENTF_KEYUP,
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        };
+        SendInput(&[input], std::mem::size_of::<INPUT>() as i32);
+    }
+}
+
 /// Low-level keyboard hook procedure
 unsafe extern "system" fn keyboard_hook_proc(
     code: i32,
@@ -155,22 +227,60 @@ unsafe extern "system" fn keyboard_hook_proc(
     if code >= 0 {
         let kb_struct = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
         let vk_code = kb_struct.vkCode;
+
+        // Don't reprocess the synthetic key-ups we inject on unlock - they'd
+        // otherwise re-enter this lock/held-key bookkeeping.
+        if (kb_struct.flags.0 & LLKHF_INJECTED.0) != 0 {
+            return CallNextHookEx(None, code, wparam, lparam);
+        }
+
+        // The physical (layout-independent) scancode, folding in the
+        // extended-key flag so e.g. the numpad Enter and main Enter (which
+        // share a base scancode) remain distinguishable.
+        let scancode = kb_struct.scanCode
+            | if (kb_struct.flags.0 & LLKHF_EXTENDED.0) != 0 { 0xE000 } else { 0 };
         let is_keydown = wparam.0 == WM_KEYDOWN as usize || wparam.0 == WM_SYSKEYDOWN as usize;
 
         HOOK_STATE.with(|hs| {
             if let Some(hook_state) = hs.borrow().as_ref() {
                 let is_locked = hook_state.state.locked.load(Ordering::SeqCst);
 
-                // Check for hotkey press (only on keydown, not modifiers themselves)
+                // Any genuine keydown resets the idle clock that drives
+                // auto-lock, including the unlock hotkey itself, so the
+                // overlay doesn't reappear the instant the user unlocks.
+                if is_keydown {
+                    hook_state
+                        .state
+                        .last_input_tick
+                        .store(crate::current_tick(), Ordering::SeqCst);
+                }
+
+                // Check for hotkey press (only on keydown, not modifiers themselves).
+                // Read the current hotkey from the shared AppState atomics
+                // rather than a value cached at hook startup, so a change
+                // saved in the settings dialog applies immediately.
+                let match_physical = hook_state.state.hotkey_match_physical.load(Ordering::SeqCst);
+                let hotkey_vk = hook_state.state.hotkey_vk.load(Ordering::SeqCst);
+                let hotkey_scancode = hook_state.state.hotkey_scancode.load(Ordering::SeqCst);
+                let hotkey_modifiers = hook_state.state.hotkey_modifiers.load(Ordering::SeqCst);
+
                 if is_keydown && !is_modifier_vk(vk_code) {
-                    if vk_code == hook_state.hotkey_vk
-                        && check_modifiers(hook_state.hotkey_modifiers)
-                    {
+                    let key_matches = if match_physical {
+                        scancode == hotkey_scancode
+                    } else {
+                        vk_code == hotkey_vk
+                    };
+
+                    if key_matches && check_modifiers(hotkey_modifiers) {
                         // Toggle lock state
                         let new_state = !is_locked;
                         hook_state.state.locked.store(new_state, Ordering::SeqCst);
                         debug!("Hotkey pressed, locked={}", new_state);
 
+                        if !new_state {
+                            release_held_keys(&hook_state.held_keys);
+                        }
+
                         // Block this keypress so it doesn't pass through
                         return LRESULT(1);
                     }
@@ -180,6 +290,19 @@ unsafe extern "system" fn keyboard_hook_proc(
                 // - The unlock hotkey modifiers (so user can press the combo)
                 // - Ctrl+Alt+Del (can't be blocked anyway, OS-level)
                 if is_locked {
+                    // A trusted device (Raw Input allowlist) bypasses the
+                    // lock entirely, e.g. a parent's own keyboard or a
+                    // hardware security key typing an OTP.
+                    if crate::raw_input::device_bypass_is_trusted(&hook_state.state) {
+                        return CallNextHookEx(None, code, wparam, lparam);
+                    }
+
+                    // Remember this key so it can be force-released once we unlock,
+                    // even modifiers that are allowed through below
+                    if is_keydown {
+                        hook_state.held_keys.borrow_mut().insert(vk_code);
+                    }
+
                     // Allow modifier keys through so user can build up the hotkey combo
                     if is_modifier_vk(vk_code) {
                         // Pass through modifier keys
@@ -7,11 +7,13 @@
 
 mod config;
 mod keyboard;
+mod mouse;
 mod overlay;
+mod raw_input;
 mod tray;
 mod settings_dialog;
 
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
 use log::{info, error};
 use single_instance::SingleInstance;
@@ -24,18 +26,102 @@ pub struct AppState {
     pub should_quit: AtomicBool,
     /// Signal to show settings dialog
     pub show_settings: AtomicBool,
+    /// Set by an overlay window on WM_DISPLAYCHANGE/WM_DPICHANGED; the tray
+    /// loop observes it and rebuilds the per-monitor overlay windows
+    pub monitors_changed: AtomicBool,
+    /// Monotonic tick count (`GetTickCount64`) of the last genuine key or
+    /// mouse event seen by the hooks, used to drive the auto-lock timer
+    pub last_input_tick: AtomicU64,
+    /// Whether the most recent Raw Input event came from a device on the
+    /// trusted-device allowlist; consulted by the keyboard/mouse hooks so
+    /// that device's input passes through even while locked. Paired with
+    /// `last_input_device_tick` since the Raw Input pump and the hooks run
+    /// on different threads and can't be synchronized event-for-event - a
+    /// `true` here is only trusted for a short window after that tick.
+    pub last_input_device_allowed: AtomicBool,
+    /// Tick (`current_tick()`) at which `last_input_device_allowed` was last
+    /// set. Zero means no Raw Input event has arrived yet.
+    pub last_input_device_tick: AtomicU64,
+    /// Device identifiers the Raw Input hook treats as trusted, kept here
+    /// (rather than thread-local) so the settings dialog can update it from
+    /// the tray thread while the hook pumps Raw Input on its own thread.
+    pub allowed_device_ids: std::sync::Mutex<Vec<String>>,
+    /// Currently active unlock hotkey, read by the keyboard hook on every
+    /// keydown rather than cached in its thread-local state, so a hotkey
+    /// change from the settings dialog takes effect immediately without
+    /// reinstalling the hook. Set on startup and whenever `keyboard::reload_hotkey`
+    /// is called.
+    pub hotkey_modifiers: AtomicU32,
+    pub hotkey_vk: AtomicU32,
+    pub hotkey_scancode: AtomicU32,
+    pub hotkey_match_physical: AtomicBool,
 }
 
 impl AppState {
-    pub fn new() -> Self {
+    pub fn new(allowed_device_ids: Vec<String>) -> Self {
         Self {
             locked: AtomicBool::new(false),
             should_quit: AtomicBool::new(false),
             show_settings: AtomicBool::new(false),
+            monitors_changed: AtomicBool::new(false),
+            last_input_tick: AtomicU64::new(current_tick()),
+            last_input_device_allowed: AtomicBool::new(false),
+            last_input_device_tick: AtomicU64::new(0),
+            allowed_device_ids: std::sync::Mutex::new(allowed_device_ids),
+            hotkey_modifiers: AtomicU32::new(0),
+            hotkey_vk: AtomicU32::new(0),
+            hotkey_scancode: AtomicU32::new(0),
+            hotkey_match_physical: AtomicBool::new(false),
         }
     }
 }
 
+/// Monotonic milliseconds since system start, used for idle-time comparisons
+pub fn current_tick() -> u64 {
+    unsafe { windows::Win32::System::SystemInformation::GetTickCount64() }
+}
+
+/// Executable file name (e.g. "Teams.exe") of the process owning the
+/// current foreground window, used to resolve per-application lock
+/// profiles. Returns `None` if there's no foreground window or its
+/// process's image name can't be queried (e.g. a protected process).
+pub fn foreground_executable_name() -> Option<String> {
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.0.is_null() {
+            return None;
+        }
+
+        let mut pid: u32 = 0;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        if pid == 0 {
+            return None;
+        }
+
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buffer = [0u16; 260];
+        let mut len = buffer.len() as u32;
+        let queried = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            windows::core::PWSTR(buffer.as_mut_ptr()),
+            &mut len,
+        );
+        let _ = windows::Win32::Foundation::CloseHandle(process);
+        queried.ok()?;
+
+        let path = String::from_utf16_lossy(&buffer[..len as usize]);
+        std::path::Path::new(&path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+    }
+}
+
 fn main() {
     // Initialize logging (only in debug builds)
     #[cfg(debug_assertions)]
@@ -52,7 +138,7 @@ fn main() {
     }
 
     // Load configuration
-    let config = match config::Config::load() {
+    let mut config = match config::Config::load() {
         Ok(c) => c,
         Err(e) => {
             error!("Failed to load config: {}", e);
@@ -60,10 +146,21 @@ fn main() {
         }
     };
 
+    // Validate the hotkey now, while we can still tell the user about it,
+    // rather than letting the keyboard hook silently fall back later.
+    if let Err(e) = config.hotkey.parse::<config::Hotkey>() {
+        error!("Invalid hotkey \"{}\": {}", config.hotkey, e);
+        show_error_message(&format!(
+            "Your configured hotkey \"{}\" is invalid ({}).\n\nFalling back to the default hotkey (Ctrl+B). Open Settings to fix it.",
+            config.hotkey, e
+        ));
+        config.hotkey = config::Config::default().hotkey;
+    }
+
     info!("Loaded config: hotkey={}, opacity={}", config.hotkey, config.opacity);
 
     // Create shared state
-    let state = Arc::new(AppState::new());
+    let state = Arc::new(AppState::new(config.allowed_device_ids.clone()));
 
     // Start keyboard hook in background thread
     let kb_state = Arc::clone(&state);
@@ -72,17 +169,38 @@ fn main() {
         keyboard::run_keyboard_hook(kb_state, kb_config);
     });
 
+    // Start mouse hook in its own background thread (low-level hooks each
+    // need their own message loop). Installed unconditionally - even with
+    // mouse locking disabled, it still feeds `last_input_tick` so the
+    // auto-lock idle timer reflects mouse-only activity.
+    let mouse_state = Arc::clone(&state);
+    let mouse_config = config.clone();
+    let mouse_thread = std::thread::spawn(move || {
+        mouse::run_mouse_hook(mouse_state, mouse_config);
+    });
+
+    // Start the Raw Input device-identification pump in its own thread too,
+    // so trusted-device detection isn't gated behind the tray loop's ~16ms
+    // frame sleep - the keyboard/mouse hooks consult `last_input_device_allowed`
+    // synchronously and need it kept as fresh as possible.
+    let raw_input_state = Arc::clone(&state);
+    let raw_input_thread = std::thread::spawn(move || {
+        raw_input::run_raw_input_hook(raw_input_state);
+    });
+
     // Run the main UI loop (tray icon + overlay management)
     // This runs on the main thread to handle Windows messages properly
     if let Err(e) = tray::run_tray_loop(Arc::clone(&state), config) {
         error!("Tray loop error: {}", e);
     }
 
-    // Signal keyboard thread to stop
+    // Signal keyboard/mouse/raw-input threads to stop
     state.should_quit.store(true, Ordering::SeqCst);
 
-    // Wait for keyboard thread to finish
+    // Wait for background hook threads to finish
     let _ = keyboard_thread.join();
+    let _ = mouse_thread.join();
+    let _ = raw_input_thread.join();
 
     info!("PawGate exiting...");
 }
@@ -0,0 +1,125 @@
+//! Low-level mouse hook for blocking pointer input
+//!
+//! Mirrors `keyboard.rs`: uses Windows SetWindowsHookExW with WH_MOUSE_LL to
+//! intercept all mouse events. When locked, swallows movement, button, and
+//! wheel events so a pet bumping the trackpad can't click or drag anything.
+
+use crate::config::Config;
+use crate::AppState;
+use log::debug;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+/// `dwExtraInfo` tag Windows stamps on mouse input synthesized from pen/touch
+/// digitizer input (the path precision touchpads and pen devices use).
+/// See the `MI_WP_SIGNATURE`/`TOUCH_MI_WMASK` convention used by the Tablet
+/// PC input stack.
+const TOUCH_EXTRA_INFO_TAG: usize = 0xFF51_5700;
+const TOUCH_EXTRA_INFO_MASK: usize = 0xFFFF_FF00;
+
+/// Thread-local storage for hook state
+/// Required because the hook callback can't capture closures
+thread_local! {
+    static HOOK_STATE: std::cell::RefCell<Option<HookState>> = const { std::cell::RefCell::new(None) };
+}
+
+struct HookState {
+    state: Arc<AppState>,
+    touchpad_only: bool,
+    /// Whether the hook should actually swallow mouse events while locked.
+    /// The hook itself always runs so `last_input_tick` reflects real mouse
+    /// activity for the auto-lock timer even with mouse locking turned off.
+    lock_mouse: bool,
+}
+
+/// Run the mouse hook message loop. Installed unconditionally (regardless of
+/// `config.lock_mouse`) so the idle clock auto-lock relies on keeps tracking
+/// mouse activity; `lock_mouse` only gates whether the hook blocks events.
+pub fn run_mouse_hook(state: Arc<AppState>, config: Config) {
+    HOOK_STATE.with(|hs| {
+        *hs.borrow_mut() = Some(HookState {
+            state: Arc::clone(&state),
+            touchpad_only: config.block_touchpad_only,
+            lock_mouse: config.lock_mouse,
+        });
+    });
+
+    let hook = unsafe {
+        SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_hook_proc), None, 0)
+            .expect("Failed to install mouse hook")
+    };
+
+    log::info!("Mouse hook installed");
+
+    unsafe {
+        let mut msg = MSG::default();
+        while !state.should_quit.load(Ordering::SeqCst) {
+            if PeekMessageW(&mut msg, None, 0, 0, PM_REMOVE).as_bool() {
+                if msg.message == WM_QUIT {
+                    break;
+                }
+                TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            } else {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+        }
+
+        let _ = UnhookWindowsHookEx(hook);
+    }
+
+    log::info!("Mouse hook removed");
+}
+
+/// Low-level mouse hook procedure
+unsafe extern "system" fn mouse_hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+        let is_blockable = matches!(
+            wparam.0 as u32,
+            WM_MOUSEMOVE
+                | WM_LBUTTONDOWN
+                | WM_LBUTTONUP
+                | WM_RBUTTONDOWN
+                | WM_RBUTTONUP
+                | WM_MBUTTONDOWN
+                | WM_MBUTTONUP
+                | WM_MOUSEWHEEL
+                | WM_MOUSEHWHEEL
+        );
+
+        if is_blockable {
+            let ms_struct = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+            let is_touch_origin =
+                (ms_struct.dwExtraInfo & TOUCH_EXTRA_INFO_MASK) == TOUCH_EXTRA_INFO_TAG;
+
+            let should_block = HOOK_STATE.with(|hs| {
+                if let Some(hook_state) = hs.borrow().as_ref() {
+                    // Any genuine mouse activity resets the idle clock that
+                    // drives auto-lock.
+                    hook_state
+                        .state
+                        .last_input_tick
+                        .store(crate::current_tick(), Ordering::SeqCst);
+
+                    let locked = hook_state.state.locked.load(Ordering::SeqCst);
+                    let device_allowed = crate::raw_input::device_bypass_is_trusted(&hook_state.state);
+                    hook_state.lock_mouse
+                        && locked
+                        && !device_allowed
+                        && (!hook_state.touchpad_only || is_touch_origin)
+                } else {
+                    false
+                }
+            });
+
+            if should_block {
+                debug!("Blocking mouse message: {:#x}", wparam.0);
+                return LRESULT(1);
+            }
+        }
+    }
+
+    CallNextHookEx(None, code, wparam, lparam)
+}
@@ -1,7 +1,8 @@
-//! Full-screen overlay window for visual feedback when keyboard is locked
+//! Full-screen overlay windows for visual feedback when keyboard is locked
 //!
-//! Creates a semi-transparent window that spans all monitors.
-//! Uses Win32 layered windows for proper transparency.
+//! Creates one semi-transparent, layered window per physical monitor rather
+//! than a single window spanning the virtual screen, so the lock text scales
+//! correctly on mixed-DPI setups. Uses Win32 layered windows for transparency.
 
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -9,43 +10,85 @@ use windows::core::{PCWSTR, w};
 use windows::Win32::Foundation::*;
 use windows::Win32::Graphics::Gdi::*;
 use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
 use windows::Win32::UI::WindowsAndMessaging::*;
 
 use crate::config::Config;
 use crate::AppState;
 
 const OVERLAY_CLASS_NAME: PCWSTR = w!("PawGateOverlay");
+const BASE_FONT_HEIGHT: i32 = 48;
+const BASE_DPI: u32 = 96;
 
-/// Thread-local state for the overlay window
+/// Thread-local state for each overlay window, keyed by HWND
 thread_local! {
-    static OVERLAY_STATE: std::cell::RefCell<Option<OverlayState>> = const { std::cell::RefCell::new(None) };
+    static OVERLAY_STATES: std::cell::RefCell<Vec<(HWND, OverlayState)>> = const { std::cell::RefCell::new(Vec::new()) };
 }
 
 struct OverlayState {
     state: Arc<AppState>,
     color: (u8, u8, u8),
     opacity: u8,
+    font_height: i32,
+    lock_mouse: bool,
 }
 
-/// Create and show the overlay window
-/// Returns the window handle
-pub fn create_overlay(state: Arc<AppState>, config: &Config) -> Option<HWND> {
+/// One overlay window per physical monitor
+pub struct Overlay {
+    windows: Vec<HWND>,
+}
+
+/// Enumerate physical monitors and their rects
+fn enumerate_monitor_rects() -> Vec<RECT> {
+    unsafe extern "system" fn collect_proc(
+        _hmonitor: HMONITOR,
+        _hdc: HDC,
+        rect: *mut RECT,
+        lparam: LPARAM,
+    ) -> BOOL {
+        let rects = &mut *(lparam.0 as *mut Vec<RECT>);
+        rects.push(*rect);
+        BOOL(1)
+    }
+
+    let mut rects: Vec<RECT> = Vec::new();
+    unsafe {
+        let _ = EnumDisplayMonitors(
+            None,
+            None,
+            Some(collect_proc),
+            LPARAM(&mut rects as *mut _ as isize),
+        );
+    }
+    rects
+}
+
+/// Get the DPI for the monitor containing the given rect (falls back to 96)
+fn dpi_for_rect(rect: RECT) -> u32 {
+    unsafe {
+        let center = POINT {
+            x: (rect.left + rect.right) / 2,
+            y: (rect.top + rect.bottom) / 2,
+        };
+        let hmonitor = MonitorFromPoint(center, MONITOR_DEFAULTTONEAREST);
+        let mut dpi_x = BASE_DPI;
+        let mut dpi_y = BASE_DPI;
+        if GetDpiForMonitor(hmonitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y).is_ok() {
+            dpi_x
+        } else {
+            BASE_DPI
+        }
+    }
+}
+
+/// Create one hidden overlay window per physical monitor
+pub fn create_overlay(state: Arc<AppState>, config: &Config) -> Option<Overlay> {
     let (r, g, b) = config.parse_overlay_color();
     let opacity = (config.opacity * 255.0) as u8;
 
-    // Store state for window procedure
-    OVERLAY_STATE.with(|os| {
-        *os.borrow_mut() = Some(OverlayState {
-            state: Arc::clone(&state),
-            color: (r, g, b),
-            opacity,
-        });
-    });
-
     unsafe {
         let hinstance = GetModuleHandleW(None).ok()?;
 
-        // Register window class
         let wc = WNDCLASSEXW {
             cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
             style: CS_HREDRAW | CS_VREDRAW,
@@ -59,69 +102,113 @@ pub fn create_overlay(state: Arc<AppState>, config: &Config) -> Option<HWND> {
 
         RegisterClassExW(&wc);
 
-        // Get virtual screen dimensions (all monitors)
-        let x = GetSystemMetrics(SM_XVIRTUALSCREEN);
-        let y = GetSystemMetrics(SM_YVIRTUALSCREEN);
-        let width = GetSystemMetrics(SM_CXVIRTUALSCREEN);
-        let height = GetSystemMetrics(SM_CYVIRTUALSCREEN);
-
-        // Create layered window
-        let hwnd = CreateWindowExW(
-            WS_EX_LAYERED | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_TRANSPARENT,
-            OVERLAY_CLASS_NAME,
-            w!("PawGate Overlay"),
-            WS_POPUP | WS_VISIBLE,
-            x,
-            y,
-            width,
-            height,
-            None,
-            None,
-            Some(hinstance.into()),
-            None,
-        )?;
+        let mut windows = Vec::new();
+
+        // Monitors at negative virtual coordinates (common when a secondary
+        // monitor is positioned above/left of the primary) are handled fine
+        // here since we position each window from its own monitor rect
+        // rather than the virtual-screen origin.
+        for rect in enumerate_monitor_rects() {
+            let dpi = dpi_for_rect(rect);
+            let font_height = BASE_FONT_HEIGHT * dpi as i32 / BASE_DPI as i32;
+
+            let hwnd = CreateWindowExW(
+                WS_EX_LAYERED | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_TRANSPARENT,
+                OVERLAY_CLASS_NAME,
+                w!("PawGate Overlay"),
+                // Created hidden - only `set_overlay_visible(true)` (on the
+                // transition into the locked state) shows it, so the
+                // semi-transparent overlay doesn't flash over every monitor
+                // for the moment between creation and the first lock check.
+                WS_POPUP,
+                rect.left,
+                rect.top,
+                rect.right - rect.left,
+                rect.bottom - rect.top,
+                None,
+                None,
+                Some(hinstance.into()),
+                None,
+            );
 
-        // Set layered window attributes for transparency
-        SetLayeredWindowAttributes(
-            hwnd,
-            COLORREF(0),
-            opacity,
-            LWA_ALPHA,
-        ).ok()?;
+            let Some(hwnd) = hwnd else { continue };
 
-        // Force a repaint
-        InvalidateRect(hwnd, None, true);
-        UpdateWindow(hwnd);
+            let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), opacity, LWA_ALPHA);
 
-        Some(hwnd)
+            OVERLAY_STATES.with(|states| {
+                states.borrow_mut().push((
+                    hwnd,
+                    OverlayState {
+                        state: Arc::clone(&state),
+                        color: (r, g, b),
+                        opacity,
+                        font_height,
+                        lock_mouse: config.lock_mouse,
+                    },
+                ));
+            });
+
+            InvalidateRect(hwnd, None, true);
+            UpdateWindow(hwnd);
+
+            windows.push(hwnd);
+        }
+
+        if windows.is_empty() {
+            None
+        } else {
+            Some(Overlay { windows })
+        }
     }
 }
 
-/// Hide and destroy the overlay window
-pub fn destroy_overlay(hwnd: HWND) {
-    unsafe {
-        let _ = DestroyWindow(hwnd);
+/// Hide and destroy all overlay windows
+pub fn destroy_overlay(overlay: &Overlay) {
+    for &hwnd in &overlay.windows {
+        unsafe {
+            let _ = DestroyWindow(hwnd);
+        }
     }
 }
 
-/// Show or hide the overlay based on lock state
-pub fn set_overlay_visible(hwnd: HWND, visible: bool) {
-    unsafe {
-        ShowWindow(hwnd, if visible { SW_SHOW } else { SW_HIDE });
-        if visible {
-            // Bring to top and repaint
-            let _ = SetWindowPos(
-                hwnd,
-                HWND_TOPMOST,
-                0, 0, 0, 0,
-                SWP_NOMOVE | SWP_NOSIZE | SWP_SHOWWINDOW,
-            );
-            InvalidateRect(hwnd, None, true);
+/// Show or hide every overlay window based on lock state
+pub fn set_overlay_visible(overlay: &Overlay, visible: bool) {
+    for &hwnd in &overlay.windows {
+        unsafe {
+            ShowWindow(hwnd, if visible { SW_SHOW } else { SW_HIDE });
+            if visible {
+                let _ = SetWindowPos(
+                    hwnd,
+                    HWND_TOPMOST,
+                    0, 0, 0, 0,
+                    SWP_NOMOVE | SWP_NOSIZE | SWP_SHOWWINDOW,
+                );
+                InvalidateRect(hwnd, None, true);
+            }
+        }
+    }
+}
+
+/// Rebuild every overlay window in place, e.g. after `WM_DISPLAYCHANGE` or
+/// `WM_DPICHANGED` when monitors are hot-plugged or rescaled
+pub fn rebuild_overlay(overlay: Overlay, state: Arc<AppState>, config: &Config) -> Option<Overlay> {
+    let was_visible = overlay
+        .windows
+        .first()
+        .map(|&hwnd| unsafe { IsWindowVisible(hwnd).as_bool() })
+        .unwrap_or(false);
+
+    destroy_overlay(&overlay);
+    let rebuilt = create_overlay(state, config);
+    if let Some(ref o) = rebuilt {
+        if was_visible {
+            set_overlay_visible(o, true);
         }
     }
+    rebuilt
 }
 
-/// Window procedure for the overlay
+/// Window procedure for an overlay window
 unsafe extern "system" fn overlay_wnd_proc(
     hwnd: HWND,
     msg: u32,
@@ -130,8 +217,8 @@ unsafe extern "system" fn overlay_wnd_proc(
 ) -> LRESULT {
     match msg {
         WM_PAINT => {
-            OVERLAY_STATE.with(|os| {
-                if let Some(state) = os.borrow().as_ref() {
+            OVERLAY_STATES.with(|states| {
+                if let Some((_, state)) = states.borrow().iter().find(|(h, _)| *h == hwnd) {
                     let mut ps = PAINTSTRUCT::default();
                     let hdc = BeginPaint(hwnd, &mut ps);
 
@@ -149,13 +236,17 @@ unsafe extern "system" fn overlay_wnd_proc(
                     FillRect(hdc, &rect, brush);
                     let _ = DeleteObject(brush);
 
-                    // Draw centered text
-                    let text = "Keyboard Locked - Press hotkey to unlock";
+                    // Draw centered text, reflecting whichever inputs are currently blocked
+                    let text = if state.lock_mouse {
+                        "Keyboard & Mouse Locked - Press hotkey to unlock"
+                    } else {
+                        "Keyboard Locked - Press hotkey to unlock"
+                    };
                     let wide_text: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
 
-                    // Create a larger font
+                    // Create a font scaled for this monitor's DPI
                     let font = CreateFontW(
-                        48, 0, 0, 0,
+                        state.font_height, 0, 0, 0,
                         FW_BOLD.0 as i32,
                         0, 0, 0,
                         DEFAULT_CHARSET.0 as u32,
@@ -192,9 +283,18 @@ unsafe extern "system" fn overlay_wnd_proc(
             LRESULT(1)
         }
 
+        WM_DISPLAYCHANGE | WM_DPICHANGED => {
+            OVERLAY_STATES.with(|states| {
+                if let Some((_, state)) = states.borrow().iter().find(|(h, _)| *h == hwnd) {
+                    state.state.monitors_changed.store(true, Ordering::SeqCst);
+                }
+            });
+            LRESULT(0)
+        }
+
         WM_DESTROY => {
-            OVERLAY_STATE.with(|os| {
-                *os.borrow_mut() = None;
+            OVERLAY_STATES.with(|states| {
+                states.borrow_mut().retain(|(h, _)| *h != hwnd);
             });
             LRESULT(0)
         }
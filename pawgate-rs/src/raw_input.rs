@@ -0,0 +1,239 @@
+//! Raw Input device identification, so a trusted device can bypass the lock
+//!
+//! The low-level keyboard/mouse hooks can't tell which physical device an
+//! event came from. Raw Input can: we register for the keyboard and mouse
+//! usage pages on a message-only window and read each `WM_INPUT`'s
+//! originating device handle via `GetRawInputDeviceInfoW`. We can't attach
+//! that identity to the low-level hook's event directly, so we record the
+//! most recently seen device's allowlist status (and the tick it was seen
+//! at) on `AppState` and have the hooks consult it - events from an
+//! allowlisted device pass through even while locked. Runs its own message
+//! loop on its own thread (mirroring `keyboard::run_keyboard_hook` and
+//! `mouse::run_mouse_hook`) rather than being pumped by the tray loop, so
+//! `WM_INPUT` is drained promptly instead of in bursts every ~16ms.
+
+use crate::AppState;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use windows::core::{w, PCWSTR};
+use windows::Win32::Foundation::*;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::Input::*;
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+const RAW_INPUT_CLASS_NAME: PCWSTR = w!("PawGateRawInput");
+const HID_USAGE_PAGE_GENERIC: u16 = 0x01;
+const HID_USAGE_GENERIC_MOUSE: u16 = 0x02;
+const HID_USAGE_GENERIC_KEYBOARD: u16 = 0x06;
+
+/// How long a `last_input_device_allowed = true` stays trusted after the
+/// `WM_INPUT` event that set it, in milliseconds. The Raw Input pump and the
+/// keyboard/mouse hooks run on separate threads and can't be correlated
+/// event-for-event, so without an expiry a stale "allowed" from one device's
+/// event could let a different, untrusted device's *next* keystrokes through
+/// while locked. Short enough to bound that window, long enough that the
+/// prompt (1 ms idle poll) pump keeps refreshing it during continuous
+/// input from the trusted device.
+const DEVICE_TRUST_WINDOW_MS: u64 = 150;
+
+thread_local! {
+    static RAW_INPUT_STATE: std::cell::RefCell<Option<RawInputState>> = const { std::cell::RefCell::new(None) };
+}
+
+struct RawInputState {
+    app_state: Arc<AppState>,
+}
+
+/// Set by `begin_learn_device`; the next device to send a `WM_INPUT` event
+/// has its identifier captured into `LEARNED_DEVICE` instead of being
+/// checked against the allowlist.
+static LEARNING: AtomicBool = AtomicBool::new(false);
+static LEARNED_DEVICE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Whether `last_input_device_allowed` is still within `DEVICE_TRUST_WINDOW_MS`
+/// of the Raw Input event that set it. Consulted by the keyboard/mouse hooks
+/// instead of the raw flag so a bypass can't outlive the event that granted it.
+pub fn device_bypass_is_trusted(state: &AppState) -> bool {
+    state.last_input_device_allowed.load(Ordering::SeqCst)
+        && crate::current_tick().saturating_sub(state.last_input_device_tick.load(Ordering::SeqCst))
+            <= DEVICE_TRUST_WINDOW_MS
+}
+
+/// Start "Learn device" capture mode; the next key or mouse event's source
+/// device identifier will be available from `take_learned_device`.
+pub fn begin_learn_device() {
+    *LEARNED_DEVICE.lock().unwrap() = None;
+    LEARNING.store(true, Ordering::SeqCst);
+}
+
+/// Take the device identifier captured since the last `begin_learn_device`
+/// call, if any device has sent input yet.
+pub fn take_learned_device() -> Option<String> {
+    LEARNED_DEVICE.lock().unwrap().take()
+}
+
+/// Create the hidden message-only window, register for raw keyboard and
+/// mouse input on it, and pump its message loop until `state.should_quit`.
+/// Runs on its own thread (spawned from `main`) so `WM_INPUT` is handled as
+/// soon as it arrives rather than waiting on the tray loop's own message
+/// pump and frame sleep.
+pub fn run_raw_input_hook(state: Arc<AppState>) {
+    let Some(hwnd) = (unsafe { create_raw_input_window(&state) }) else {
+        log::error!("Failed to create Raw Input window; trusted-device bypass disabled");
+        return;
+    };
+
+    log::info!("Raw Input hook installed");
+
+    unsafe {
+        let mut msg = MSG::default();
+        while !state.should_quit.load(Ordering::SeqCst) {
+            if PeekMessageW(&mut msg, None, 0, 0, PM_REMOVE).as_bool() {
+                if msg.message == WM_QUIT {
+                    break;
+                }
+                TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            } else {
+                // Short idle sleep: long enough to avoid busy-waiting, short
+                // enough that a device's trust status is refreshed well
+                // within `DEVICE_TRUST_WINDOW_MS` of its events.
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            }
+        }
+
+        let _ = DestroyWindow(hwnd);
+    }
+
+    log::info!("Raw Input hook removed");
+}
+
+unsafe fn create_raw_input_window(state: &Arc<AppState>) -> Option<HWND> {
+    let hinstance = GetModuleHandleW(None).ok()?;
+
+    let wc = WNDCLASSEXW {
+        cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+        lpfnWndProc: Some(raw_input_wnd_proc),
+        hInstance: hinstance.into(),
+        lpszClassName: RAW_INPUT_CLASS_NAME,
+        ..Default::default()
+    };
+    RegisterClassExW(&wc);
+
+    let hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        RAW_INPUT_CLASS_NAME,
+        PCWSTR::null(),
+        WINDOW_STYLE(0),
+        0, 0, 0, 0,
+        Some(HWND_MESSAGE),
+        None,
+        Some(hinstance.into()),
+        None,
+    )?;
+
+    RAW_INPUT_STATE.with(|rs| {
+        *rs.borrow_mut() = Some(RawInputState {
+            app_state: Arc::clone(state),
+        });
+    });
+
+    let devices = [
+        RAWINPUTDEVICE {
+            usUsagePage: HID_USAGE_PAGE_GENERIC,
+            usUsage: HID_USAGE_GENERIC_KEYBOARD,
+            dwFlags: RIDEV_INPUTSINK,
+            hwndTarget: hwnd,
+        },
+        RAWINPUTDEVICE {
+            usUsagePage: HID_USAGE_PAGE_GENERIC,
+            usUsage: HID_USAGE_GENERIC_MOUSE,
+            dwFlags: RIDEV_INPUTSINK,
+            hwndTarget: hwnd,
+        },
+    ];
+    let _ = RegisterRawInputDevices(&devices, std::mem::size_of::<RAWINPUTDEVICE>() as u32);
+
+    Some(hwnd)
+}
+
+/// Update the allowlist in place, e.g. after settings are saved
+pub fn update_allowlist(state: &AppState, allowlist: Vec<String>) {
+    *state.allowed_device_ids.lock().unwrap() = allowlist;
+}
+
+/// Resolve a stable identifier for a raw input device: its device name/path,
+/// which for USB HID devices encodes the VID/PID (e.g.
+/// `\\?\HID#VID_046D&PID_C52B#...`).
+unsafe fn device_identifier(hdevice: HANDLE) -> Option<String> {
+    let mut size: u32 = 0;
+    GetRawInputDeviceInfoW(Some(HRAWINPUT(hdevice.0)), RIDI_DEVICENAME, None, &mut size);
+    if size == 0 {
+        return None;
+    }
+
+    let mut buffer = vec![0u16; size as usize];
+    let written = GetRawInputDeviceInfoW(
+        Some(HRAWINPUT(hdevice.0)),
+        RIDI_DEVICENAME,
+        Some(buffer.as_mut_ptr() as *mut std::ffi::c_void),
+        &mut size,
+    );
+    if written == u32::MAX {
+        return None;
+    }
+
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    Some(String::from_utf16_lossy(&buffer[..len]))
+}
+
+unsafe extern "system" fn raw_input_wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == WM_INPUT {
+        let mut header = RAWINPUTHEADER::default();
+        let mut size = std::mem::size_of::<RAWINPUTHEADER>() as u32;
+        let got = GetRawInputData(
+            HRAWINPUT(lparam.0 as *mut std::ffi::c_void),
+            RID_HEADER,
+            Some(&mut header as *mut _ as *mut std::ffi::c_void),
+            &mut size,
+            std::mem::size_of::<RAWINPUTHEADER>() as u32,
+        );
+
+        if got != u32::MAX {
+            if let Some(id) = device_identifier(header.hDevice) {
+                if LEARNING.swap(false, Ordering::SeqCst) {
+                    *LEARNED_DEVICE.lock().unwrap() = Some(id);
+                } else {
+                    RAW_INPUT_STATE.with(|rs| {
+                        if let Some(state) = rs.borrow().as_ref() {
+                            let allowed = state
+                                .app_state
+                                .allowed_device_ids
+                                .lock()
+                                .unwrap()
+                                .iter()
+                                .any(|a| a == &id);
+                            state
+                                .app_state
+                                .last_input_device_allowed
+                                .store(allowed, Ordering::SeqCst);
+                            state
+                                .app_state
+                                .last_input_device_tick
+                                .store(crate::current_tick(), Ordering::SeqCst);
+                        }
+                    });
+                }
+            }
+        }
+
+        return DefWindowProcW(hwnd, msg, wparam, lparam);
+    }
+
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
@@ -12,22 +12,76 @@ use windows::core::{w, PCWSTR};
 use windows::Win32::Foundation::*;
 use windows::Win32::Graphics::Gdi::*;
 use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::Controls::Dialogs::{ChooseColorW, CHOOSECOLORW, CC_FULLOPEN, CC_RGBINIT};
 use windows::Win32::UI::Controls::*;
+use windows::Win32::UI::HiDpi::{GetDpiForMonitor, GetDpiForWindow, MDT_EFFECTIVE_DPI};
 use windows::Win32::UI::WindowsAndMessaging::*;
 
+/// Windows' baseline DPI; every coordinate and font height in this file is
+/// authored for this DPI and scaled up by `scale_for_dpi` at dialog creation
+/// time and again on `WM_DPICHANGED`.
+const BASE_DPI: u32 = 96;
+
+/// Scale a design-time (96 DPI) pixel value to `dpi`.
+fn scale_for_dpi(value: i32, dpi: u32) -> i32 {
+    value * dpi as i32 / BASE_DPI as i32
+}
+
 // Control IDs
 const ID_OK: i32 = 1;
 const ID_CANCEL: i32 = 2;
+const ID_APPLY: i32 = 3;
 const ID_HOTKEY_EDIT: i32 = 100;
 const ID_OPACITY_SLIDER: i32 = 101;
 const ID_OPACITY_LABEL: i32 = 102;
 const ID_COLOR_COMBO: i32 = 103;
 const ID_NOTIFICATIONS_CHECK: i32 = 104;
+const ID_LOCK_MOUSE_CHECK: i32 = 105;
+const ID_TRUSTED_DEVICES_LIST: i32 = 106;
+const ID_LEARN_DEVICE_BUTTON: i32 = 107;
+const ID_REMOVE_DEVICE_BUTTON: i32 = 108;
+const ID_COLOR_PREVIEW: i32 = 109;
+const ID_PROFILE_COMBO: i32 = 110;
+const ID_PROFILE_ADD_BUTTON: i32 = 111;
+const ID_PROFILE_REMOVE_BUTTON: i32 = 112;
+const ID_TAB_CONTROL: i32 = 113;
+const ID_PAGE_GENERAL: i32 = 114;
+const ID_PAGE_PROFILES: i32 = 115;
+const ID_PAGE_DEVICES: i32 = 116;
+
+const PREVIEW_CLASS_NAME: PCWSTR = w!("PawGateColorPreview");
+/// Window class backing each tab's page container - a plain, undecorated
+/// child window that just hosts that page's controls, shown/hidden as the
+/// tab selection changes
+const PAGE_CLASS_NAME: PCWSTR = w!("PawGateSettingsPage");
+/// Tab labels, in `ID_PAGE_GENERAL`/`ID_PAGE_PROFILES`/`ID_PAGE_DEVICES` order
+const TAB_TITLES: &[&str] = &["General", "Profiles", "Devices"];
+
+/// Sentinel shown as the first entry of the profile selector, representing
+/// the global defaults rather than any specific application override
+const PROFILE_GLOBAL_SENTINEL: &str = "(Global Default)";
 
 /// Thread-local storage for dialog state
 thread_local! {
     static DIALOG_CONFIG: RefCell<Option<Config>> = const { RefCell::new(None) };
     static DIALOG_RESULT: RefCell<Option<Config>> = const { RefCell::new(None) };
+    /// Brush backing the live overlay-color preview swatch, rebuilt whenever
+    /// the opacity slider or color combo changes
+    static PREVIEW_BRUSH: RefCell<Option<HBRUSH>> = const { RefCell::new(None) };
+    /// Executable name (or `PROFILE_GLOBAL_SENTINEL`) of whichever scope the
+    /// hotkey/opacity/color/notifications controls currently show, so their
+    /// values can be committed to the right place before switching scopes
+    static PROFILE_SELECTION: RefCell<String> = RefCell::new(PROFILE_GLOBAL_SENTINEL.to_string());
+    /// The hotkey string last loaded into `ID_HOTKEY_EDIT` for the scope
+    /// named by `PROFILE_SELECTION`, before the user touched the control.
+    /// The `msctls_hotkey32` control can't represent the Win modifier, so a
+    /// `win+...` hotkey round-trips through it with Win silently dropped;
+    /// `commit_profile_edits` compares the control against this to tell an
+    /// untouched control (keep the original, Win and all) from a real edit.
+    static LOADED_HOTKEY: RefCell<Option<String>> = const { RefCell::new(None) };
+    /// Index into `TAB_TITLES` of whichever tab is active, so `WM_DPICHANGED`
+    /// can restore it after tearing down and recreating every control
+    static ACTIVE_TAB: RefCell<Option<i32>> = const { RefCell::new(None) };
 }
 
 /// Color presets - all colorblind-friendly
@@ -40,6 +94,10 @@ const COLOR_PRESETS: &[(&str, &str)] = &[
     ("Deep Teal", "#00695C"),
 ];
 
+/// Sentinel shown as the last entry of `ID_COLOR_COMBO`; selecting it opens
+/// the common `ChooseColorW` picker instead of naming a fixed preset
+const CUSTOM_COLOR_SENTINEL: &str = "Custom\u{2026}";
+
 /// Show the settings dialog and return updated config if OK was pressed
 pub fn show_settings_dialog(current_config: &Config) -> Option<Config> {
     // Store current config for the dialog
@@ -49,6 +107,11 @@ pub fn show_settings_dialog(current_config: &Config) -> Option<Config> {
     DIALOG_RESULT.with(|r| {
         *r.borrow_mut() = None;
     });
+    PROFILE_SELECTION.with(|s| {
+        *s.borrow_mut() = PROFILE_GLOBAL_SENTINEL.to_string();
+    });
+    LOADED_HOTKEY.with(|h| *h.borrow_mut() = Some(current_config.hotkey.clone()));
+    ACTIVE_TAB.with(|t| *t.borrow_mut() = None);
 
     unsafe {
         let hinstance = GetModuleHandleW(None).ok()?;
@@ -68,13 +131,29 @@ pub fn show_settings_dialog(current_config: &Config) -> Option<Config> {
 
         RegisterClassExW(&wc);
 
-        // Calculate center position
-        let screen_width = GetSystemMetrics(SM_CXSCREEN);
-        let screen_height = GetSystemMetrics(SM_CYSCREEN);
-        let dialog_width = 400;
-        let dialog_height = 320;
-        let x = (screen_width - dialog_width) / 2;
-        let y = (screen_height - dialog_height) / 2;
+        // Center the dialog on the monitor under the cursor, at that
+        // monitor's own DPI, rather than assuming a single SM_CXSCREEN
+        // virtual screen - this keeps it correctly sized and positioned on
+        // mixed-DPI and multi-monitor setups
+        let mut cursor_pos = POINT::default();
+        let _ = GetCursorPos(&mut cursor_pos);
+        let hmonitor = MonitorFromPoint(cursor_pos, MONITOR_DEFAULTTONEAREST);
+
+        let mut dpi_x = BASE_DPI;
+        let mut dpi_y = BASE_DPI;
+        let _ = GetDpiForMonitor(hmonitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y);
+
+        let mut monitor_info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        let _ = GetMonitorInfoW(hmonitor, &mut monitor_info);
+        let work_area = monitor_info.rcWork;
+
+        let dialog_width = scale_for_dpi(400, dpi_x);
+        let dialog_height = scale_for_dpi(500, dpi_x);
+        let x = work_area.left + ((work_area.right - work_area.left) - dialog_width) / 2;
+        let y = work_area.top + ((work_area.bottom - work_area.top) - dialog_height) / 2;
 
         // Create dialog window
         let hwnd = CreateWindowExW(
@@ -114,25 +193,167 @@ pub fn show_settings_dialog(current_config: &Config) -> Option<Config> {
     DIALOG_RESULT.with(|r| r.borrow_mut().take())
 }
 
+/// Build the tab strip, one page container per `TAB_TITLES` entry, and the
+/// OK/Apply/Cancel buttons pinned below them. Each page is built by its own
+/// `create_*_page_controls` so new settings only grow their own page instead
+/// of re-tuning `y +=` offsets across the whole window.
 unsafe fn create_dialog_controls(hwnd: HWND, config: &Config) {
     let hinstance = GetModuleHandleW(None).ok();
 
-    // Get default font
-    let font = get_default_font();
+    // Every coordinate and the font height below are authored for 96 DPI
+    // and scaled up to whatever DPI this dialog actually landed on, so it
+    // doesn't render tiny on a 150%/200% display
+    let dpi = GetDpiForWindow(hwnd);
+    let s = |v: i32| scale_for_dpi(v, dpi);
+
+    let font = get_default_font(dpi);
+    register_page_class(hinstance);
+
+    // Register the trackbar, hotkey-capture and tab common control classes
+    // before any of them are created below
+    let icc = INITCOMMONCONTROLSEX {
+        dwSize: std::mem::size_of::<INITCOMMONCONTROLSEX>() as u32,
+        dwICC: ICC_BAR_CLASSES | ICC_HOTKEY_CLASS | ICC_TAB_CLASSES,
+    };
+    InitCommonControlsEx(&icc);
+
+    let strip_width = s(360);
+
+    let tab = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        w!("SysTabControl32"),
+        PCWSTR::null(),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP,
+        s(20), s(20), strip_width, s(24),
+        Some(hwnd),
+        HMENU(ID_TAB_CONTROL as *mut std::ffi::c_void),
+        hinstance,
+        None,
+    );
+    if let Some(h) = tab {
+        send_font_message(h, font);
+        for title in TAB_TITLES {
+            let mut wide_title = to_wide(title);
+            let mut item = TCITEMW {
+                mask: TCIF_TEXT,
+                pszText: windows::core::PWSTR(wide_title.as_mut_ptr()),
+                ..Default::default()
+            };
+            SendMessageW(
+                h,
+                TCM_INSERTITEMW,
+                WPARAM(usize::MAX),
+                LPARAM(&mut item as *mut _ as isize),
+            );
+        }
+        SendMessageW(h, TCM_SETCURSEL, WPARAM(0), LPARAM(0));
+    }
+
+    let page_y = s(50);
+    let page_height = s(340);
+    let page_ids = [
+        (ID_PAGE_GENERAL, true),
+        (ID_PAGE_PROFILES, false),
+        (ID_PAGE_DEVICES, false),
+    ];
+    let mut pages = Vec::with_capacity(page_ids.len());
+    for (id, visible) in page_ids {
+        let style = if visible {
+            WS_CHILD | WS_VISIBLE
+        } else {
+            WS_CHILD
+        };
+        pages.push((
+            id,
+            CreateWindowExW(
+                WS_EX_CLIENTEDGE,
+                PAGE_CLASS_NAME,
+                PCWSTR::null(),
+                style,
+                s(20), page_y, strip_width, page_height,
+                Some(hwnd),
+                HMENU(id as *mut std::ffi::c_void),
+                hinstance,
+                None,
+            ),
+        ));
+    }
+    for (id, page) in pages {
+        let Some(page) = page else { continue };
+        match id {
+            ID_PAGE_GENERAL => create_general_page_controls(page, config, font, dpi),
+            ID_PAGE_PROFILES => create_profiles_page_controls(page, config, font, dpi),
+            ID_PAGE_DEVICES => create_devices_page_controls(page, config, font, dpi),
+            _ => unreachable!(),
+        }
+    }
+
+    let button_y = page_y + page_height + s(10);
+
+    let ok_button = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        w!("BUTTON"),
+        w!("OK"),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_DEFPUSHBUTTON as u32),
+        s(110), button_y, s(80), s(28),
+        Some(hwnd),
+        HMENU(ID_OK as *mut std::ffi::c_void),
+        hinstance,
+        None,
+    );
+    if let Some(h) = ok_button {
+        send_font_message(h, font);
+    }
+
+    let cancel_button = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        w!("BUTTON"),
+        w!("Cancel"),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP,
+        s(200), button_y, s(80), s(28),
+        Some(hwnd),
+        HMENU(ID_CANCEL as *mut std::ffi::c_void),
+        hinstance,
+        None,
+    );
+    if let Some(h) = cancel_button {
+        send_font_message(h, font);
+    }
 
-    let mut y = 20;
-    let label_width = 120;
-    let control_x = 140;
-    let control_width = 220;
+    let apply_button = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        w!("BUTTON"),
+        w!("Apply"),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP,
+        s(290), button_y, s(80), s(28),
+        Some(hwnd),
+        HMENU(ID_APPLY as *mut std::ffi::c_void),
+        hinstance,
+        None,
+    );
+    if let Some(h) = apply_button {
+        send_font_message(h, font);
+    }
+}
+
+/// "General" page: the hotkey, opacity and overlay color controls, scoped to
+/// whichever profile `ID_PROFILE_COMBO` (on the "Profiles" page) selects.
+unsafe fn create_general_page_controls(page: HWND, config: &Config, font: HFONT, dpi: u32) {
+    let hinstance = GetModuleHandleW(None).ok();
+    let s = |v: i32| scale_for_dpi(v, dpi);
+
+    let mut y = s(16);
+    let label_width = s(100);
+    let control_x = s(120);
+    let control_width = s(200);
 
-    // Hotkey label
     let hotkey_label = CreateWindowExW(
         WINDOW_EX_STYLE(0),
         w!("STATIC"),
         w!("Hotkey:"),
         WS_CHILD | WS_VISIBLE | WINDOW_STYLE(SS_RIGHT as u32),
-        20, y + 2, label_width, 20,
-        Some(hwnd),
+        s(10), y + s(2), label_width, s(20),
+        Some(page),
         None,
         hinstance,
         None,
@@ -141,32 +362,50 @@ unsafe fn create_dialog_controls(hwnd: HWND, config: &Config) {
         send_font_message(h, font);
     }
 
-    // Hotkey edit box
+    // Hotkey capture control - native msctls_hotkey32 so only a real,
+    // registerable chord can end up in it, with live visual feedback as
+    // the user presses keys
     let hotkey_edit = CreateWindowExW(
         WS_EX_CLIENTEDGE,
-        w!("EDIT"),
-        PCWSTR(to_wide(&config.hotkey).as_ptr()),
-        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(ES_AUTOHSCROLL as u32),
-        control_x, y, control_width, 24,
-        Some(hwnd),
+        w!("msctls_hotkey32"),
+        PCWSTR::null(),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP,
+        control_x, y, control_width, s(24),
+        Some(page),
         HMENU(ID_HOTKEY_EDIT as *mut std::ffi::c_void),
         hinstance,
         None,
     );
     if let Some(h) = hotkey_edit {
         send_font_message(h, font);
+        if let Ok(hotkey) = config.hotkey.parse::<crate::config::Hotkey>() {
+            let (vk, modifiers) = hotkey_to_hkm_bytes(hotkey);
+            SendMessageW(
+                h,
+                HKM_SETHOTKEY,
+                WPARAM(((modifiers as usize) << 8) | vk as usize),
+                LPARAM(0),
+            );
+        }
+        // Forbid chords with no modifier or with Shift alone, falling back
+        // to Ctrl+<key> so whatever the control accepts is registerable
+        SendMessageW(
+            h,
+            HKM_SETRULES,
+            WPARAM((HKCOMB_NONE | HKCOMB_S) as usize),
+            LPARAM(HOTKEYF_CONTROL as isize),
+        );
     }
 
-    y += 40;
+    y += s(36);
 
-    // Opacity label
     let opacity_label = CreateWindowExW(
         WINDOW_EX_STYLE(0),
         w!("STATIC"),
         w!("Opacity:"),
         WS_CHILD | WS_VISIBLE | WINDOW_STYLE(SS_RIGHT as u32),
-        20, y + 2, label_width, 20,
-        Some(hwnd),
+        s(10), y + s(2), label_width, s(20),
+        Some(page),
         None,
         hinstance,
         None,
@@ -175,21 +414,13 @@ unsafe fn create_dialog_controls(hwnd: HWND, config: &Config) {
         send_font_message(h, font);
     }
 
-    // Opacity slider (trackbar)
-    // Initialize common controls for trackbar
-    let icc = INITCOMMONCONTROLSEX {
-        dwSize: std::mem::size_of::<INITCOMMONCONTROLSEX>() as u32,
-        dwICC: ICC_BAR_CLASSES,
-    };
-    InitCommonControlsEx(&icc);
-
     let slider = CreateWindowExW(
         WINDOW_EX_STYLE(0),
         w!("msctls_trackbar32"),
         PCWSTR::null(),
         WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(TBS_AUTOTICKS as u32),
-        control_x, y, control_width - 50, 30,
-        Some(hwnd),
+        control_x, y, control_width - s(50), s(30),
+        Some(page),
         HMENU(ID_OPACITY_SLIDER as *mut std::ffi::c_void),
         hinstance,
         None,
@@ -201,15 +432,14 @@ unsafe fn create_dialog_controls(hwnd: HWND, config: &Config) {
         SendMessageW(h, TBM_SETTICFREQ, WPARAM(10), LPARAM(0));
     }
 
-    // Opacity value label
     let opacity_value = format!("{}%", (config.opacity * 100.0) as i32);
     let opacity_value_label = CreateWindowExW(
         WINDOW_EX_STYLE(0),
         w!("STATIC"),
         PCWSTR(to_wide(&opacity_value).as_ptr()),
         WS_CHILD | WS_VISIBLE | WINDOW_STYLE(SS_LEFT as u32),
-        control_x + control_width - 40, y + 5, 40, 20,
-        Some(hwnd),
+        control_x + control_width - s(40), y + s(5), s(40), s(20),
+        Some(page),
         HMENU(ID_OPACITY_LABEL as *mut std::ffi::c_void),
         hinstance,
         None,
@@ -218,16 +448,15 @@ unsafe fn create_dialog_controls(hwnd: HWND, config: &Config) {
         send_font_message(h, font);
     }
 
-    y += 50;
+    y += s(46);
 
-    // Color label
     let color_label = CreateWindowExW(
         WINDOW_EX_STYLE(0),
         w!("STATIC"),
         w!("Overlay Color:"),
         WS_CHILD | WS_VISIBLE | WINDOW_STYLE(SS_RIGHT as u32),
-        20, y + 2, label_width, 20,
-        Some(hwnd),
+        s(10), y + s(2), label_width, s(20),
+        Some(page),
         None,
         hinstance,
         None,
@@ -236,42 +465,147 @@ unsafe fn create_dialog_controls(hwnd: HWND, config: &Config) {
         send_font_message(h, font);
     }
 
-    // Color combo box
+    // Color combo box (narrower than the other controls to leave room for
+    // the live preview swatch beside it)
+    let color_combo_width = control_width - s(40);
     let color_combo = CreateWindowExW(
         WINDOW_EX_STYLE(0),
         w!("COMBOBOX"),
         PCWSTR::null(),
         WS_CHILD | WS_VISIBLE | WS_TABSTOP | WS_VSCROLL | WINDOW_STYLE((CBS_DROPDOWNLIST) as u32),
-        control_x, y, control_width, 200,
-        Some(hwnd),
+        control_x, y, color_combo_width, s(200),
+        Some(page),
         HMENU(ID_COLOR_COMBO as *mut std::ffi::c_void),
         hinstance,
         None,
     );
     if let Some(h) = color_combo {
         send_font_message(h, font);
-        // Add color presets
-        let mut selected_idx = 0i32;
-        for (idx, (name, hex)) in COLOR_PRESETS.iter().enumerate() {
-            let wide_name = to_wide(name);
-            SendMessageW(h, CB_ADDSTRING, WPARAM(0), LPARAM(wide_name.as_ptr() as isize));
-            if *hex == config.overlay_color {
-                selected_idx = idx as i32;
-            }
+        populate_color_combo(h, config, &config.overlay_color);
+    }
+
+    // Live preview swatch - a small WS_EX_LAYERED window filled with the
+    // selected preset color at the current opacity, so users see what the
+    // overlay will actually look like before locking
+    register_preview_class(hinstance);
+    let (pr, pg, pb) = crate::config::parse_hex_color(&config.overlay_color);
+    set_preview_brush(pr, pg, pb);
+    let preview = CreateWindowExW(
+        WS_EX_LAYERED | WS_EX_CLIENTEDGE,
+        PREVIEW_CLASS_NAME,
+        PCWSTR::null(),
+        WS_CHILD | WS_VISIBLE,
+        control_x + color_combo_width + s(8), y, s(32), s(24),
+        Some(page),
+        HMENU(ID_COLOR_PREVIEW as *mut std::ffi::c_void),
+        hinstance,
+        None,
+    );
+    if let Some(h) = preview {
+        let alpha = (config.opacity * 255.0) as u8;
+        let _ = SetLayeredWindowAttributes(h, COLORREF(0), alpha, LWA_ALPHA);
+    }
+}
+
+/// "Profiles" page: the selector that switches which scope the "General"
+/// page's controls edit (global defaults, or a specific application).
+unsafe fn create_profiles_page_controls(page: HWND, config: &Config, font: HFONT, dpi: u32) {
+    let hinstance = GetModuleHandleW(None).ok();
+    let s = |v: i32| scale_for_dpi(v, dpi);
+
+    let y = s(16);
+    let profile_label = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        w!("STATIC"),
+        w!("Profile:"),
+        WS_CHILD | WS_VISIBLE | WINDOW_STYLE(SS_LEFT as u32),
+        s(10), y, s(330), s(20),
+        Some(page),
+        None,
+        hinstance,
+        None,
+    );
+    if let Some(h) = profile_label {
+        send_font_message(h, font);
+    }
+
+    let combo_y = y + s(24);
+    let profile_combo = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        w!("COMBOBOX"),
+        PCWSTR::null(),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WS_VSCROLL | WINDOW_STYLE(CBS_DROPDOWN as u32),
+        s(10), combo_y, s(240), s(200),
+        Some(page),
+        HMENU(ID_PROFILE_COMBO as *mut std::ffi::c_void),
+        hinstance,
+        None,
+    );
+    if let Some(h) = profile_combo {
+        send_font_message(h, font);
+        SendMessageW(
+            h,
+            CB_ADDSTRING,
+            WPARAM(0),
+            LPARAM(to_wide(PROFILE_GLOBAL_SENTINEL).as_ptr() as isize),
+        );
+        for profile in &config.profiles {
+            SendMessageW(
+                h,
+                CB_ADDSTRING,
+                WPARAM(0),
+                LPARAM(to_wide(&profile.executable).as_ptr() as isize),
+            );
         }
-        SendMessageW(h, CB_SETCURSEL, WPARAM(selected_idx as usize), LPARAM(0));
+        SendMessageW(h, CB_SETCURSEL, WPARAM(0), LPARAM(0));
     }
 
-    y += 40;
+    let profile_add_button = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        w!("BUTTON"),
+        w!("Add"),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP,
+        s(255), combo_y, s(40), s(24),
+        Some(page),
+        HMENU(ID_PROFILE_ADD_BUTTON as *mut std::ffi::c_void),
+        hinstance,
+        None,
+    );
+    if let Some(h) = profile_add_button {
+        send_font_message(h, font);
+    }
+
+    let profile_remove_button = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        w!("BUTTON"),
+        w!("Del"),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP,
+        s(300), combo_y, s(40), s(24),
+        Some(page),
+        HMENU(ID_PROFILE_REMOVE_BUTTON as *mut std::ffi::c_void),
+        hinstance,
+        None,
+    );
+    if let Some(h) = profile_remove_button {
+        send_font_message(h, font);
+    }
+}
+
+/// "Devices" page: notifications, mouse blocking and the Raw Input trusted
+/// device allowlist.
+unsafe fn create_devices_page_controls(page: HWND, config: &Config, font: HFONT, dpi: u32) {
+    let hinstance = GetModuleHandleW(None).ok();
+    let s = |v: i32| scale_for_dpi(v, dpi);
+
+    let mut y = s(16);
 
-    // Notifications checkbox
     let notifications_check = CreateWindowExW(
         WINDOW_EX_STYLE(0),
         w!("BUTTON"),
         w!("Enable notifications"),
         WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_AUTOCHECKBOX as u32),
-        control_x, y, control_width, 24,
-        Some(hwnd),
+        s(10), y, s(330), s(24),
+        Some(page),
         HMENU(ID_NOTIFICATIONS_CHECK as *mut std::ffi::c_void),
         hinstance,
         None,
@@ -283,67 +617,281 @@ unsafe fn create_dialog_controls(hwnd: HWND, config: &Config) {
         }
     }
 
-    y += 50;
+    y += s(28);
+
+    let lock_mouse_check = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        w!("BUTTON"),
+        w!("Also block mouse/trackpad while locked"),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_AUTOCHECKBOX as u32),
+        s(10), y, s(330), s(24),
+        Some(page),
+        HMENU(ID_LOCK_MOUSE_CHECK as *mut std::ffi::c_void),
+        hinstance,
+        None,
+    );
+    if let Some(h) = lock_mouse_check {
+        send_font_message(h, font);
+        if config.lock_mouse {
+            SendMessageW(h, BM_SETCHECK, WPARAM(BST_CHECKED.0 as usize), LPARAM(0));
+        }
+    }
+
+    y += s(32);
 
-    // Note about hotkey restart
-    let note_label = CreateWindowExW(
+    let trusted_label = CreateWindowExW(
         WINDOW_EX_STYLE(0),
         w!("STATIC"),
-        w!("Note: Hotkey changes require restart"),
-        WS_CHILD | WS_VISIBLE | WINDOW_STYLE((SS_CENTER) as u32),
-        20, y, 360, 20,
-        Some(hwnd),
+        w!("Trusted devices:"),
+        WS_CHILD | WS_VISIBLE | WINDOW_STYLE(SS_LEFT as u32),
+        s(10), y, s(330), s(20),
+        Some(page),
         None,
         hinstance,
         None,
     );
-    if let Some(h) = note_label {
+    if let Some(h) = trusted_label {
         send_font_message(h, font);
     }
 
-    y += 40;
+    y += s(20);
 
-    // OK button
-    let ok_button = CreateWindowExW(
+    // Trusted devices listbox - events from a device on this list pass
+    // through even while locked (e.g. a parent's keyboard, a hardware
+    // security key typing an OTP)
+    let devices_list = CreateWindowExW(
+        WS_EX_CLIENTEDGE,
+        w!("LISTBOX"),
+        PCWSTR::null(),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WS_VSCROLL | WINDOW_STYLE(LBS_NOTIFY as u32),
+        s(10), y, s(330), s(100),
+        Some(page),
+        HMENU(ID_TRUSTED_DEVICES_LIST as *mut std::ffi::c_void),
+        hinstance,
+        None,
+    );
+    if let Some(h) = devices_list {
+        send_font_message(h, font);
+        for id in &config.allowed_device_ids {
+            SendMessageW(h, LB_ADDSTRING, WPARAM(0), LPARAM(to_wide(id).as_ptr() as isize));
+        }
+    }
+
+    y += s(104);
+
+    let learn_button = CreateWindowExW(
         WINDOW_EX_STYLE(0),
         w!("BUTTON"),
-        w!("OK"),
-        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_DEFPUSHBUTTON as u32),
-        200, y, 80, 28,
-        Some(hwnd),
-        HMENU(ID_OK as *mut std::ffi::c_void),
+        w!("Learn device..."),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP,
+        s(10), y, s(150), s(26),
+        Some(page),
+        HMENU(ID_LEARN_DEVICE_BUTTON as *mut std::ffi::c_void),
         hinstance,
         None,
     );
-    if let Some(h) = ok_button {
+    if let Some(h) = learn_button {
         send_font_message(h, font);
     }
 
-    // Cancel button
-    let cancel_button = CreateWindowExW(
+    let remove_button = CreateWindowExW(
         WINDOW_EX_STYLE(0),
         w!("BUTTON"),
-        w!("Cancel"),
+        w!("Remove selected"),
         WS_CHILD | WS_VISIBLE | WS_TABSTOP,
-        290, y, 80, 28,
-        Some(hwnd),
-        HMENU(ID_CANCEL as *mut std::ffi::c_void),
+        s(190), y, s(150), s(26),
+        Some(page),
+        HMENU(ID_REMOVE_DEVICE_BUTTON as *mut std::ffi::c_void),
         hinstance,
         None,
     );
-    if let Some(h) = cancel_button {
+    if let Some(h) = remove_button {
         send_font_message(h, font);
     }
 }
 
-unsafe fn get_default_font() -> HFONT {
+/// Register the page container's window class, if it isn't already.
+/// Calling `RegisterClassExW` twice for the same name simply fails
+/// harmlessly, so no "already registered" tracking is needed.
+unsafe fn register_page_class(hinstance: Option<HMODULE>) {
+    let wc = WNDCLASSEXW {
+        cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+        lpfnWndProc: Some(DefWindowProcW),
+        hInstance: hinstance.unwrap_or_default().into(),
+        hCursor: LoadCursorW(None, IDC_ARROW).ok(),
+        hbrBackground: HBRUSH((COLOR_3DFACE.0 + 1) as *mut std::ffi::c_void),
+        lpszClassName: PAGE_CLASS_NAME,
+        ..Default::default()
+    };
+    RegisterClassExW(&wc);
+}
+
+/// Show whichever page container `ID_TAB_CONTROL`'s current selection names
+/// and hide the other two, called on `TCN_SELCHANGE`.
+unsafe fn show_active_tab_page(hwnd: HWND) {
+    let Some(tab) = find_control(hwnd, ID_TAB_CONTROL) else {
+        return;
+    };
+    let selected = SendMessageW(tab, TCM_GETCURSEL, WPARAM(0), LPARAM(0)).0;
+    for (idx, id) in [ID_PAGE_GENERAL, ID_PAGE_PROFILES, ID_PAGE_DEVICES].iter().enumerate() {
+        if let Some(page) = find_control(hwnd, *id) {
+            let _ = ShowWindow(page, if idx as isize == selected { SW_SHOW } else { SW_HIDE });
+        }
+    }
+}
+
+/// Register the color-preview swatch's window class, if it isn't already.
+/// Calling `RegisterClassExW` twice for the same name simply fails
+/// harmlessly, so no "already registered" tracking is needed.
+unsafe fn register_preview_class(hinstance: Option<HMODULE>) {
+    let wc = WNDCLASSEXW {
+        cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+        lpfnWndProc: Some(preview_wnd_proc),
+        hInstance: hinstance.unwrap_or_default().into(),
+        hCursor: LoadCursorW(None, IDC_ARROW).ok(),
+        lpszClassName: PREVIEW_CLASS_NAME,
+        ..Default::default()
+    };
+    RegisterClassExW(&wc);
+}
+
+/// Replace the preview swatch's brush with a solid fill of `(r, g, b)`,
+/// deleting the previous brush. Does not repaint the swatch window.
+unsafe fn set_preview_brush(r: u8, g: u8, b: u8) {
+    let brush = CreateSolidBrush(COLORREF(((b as u32) << 16) | ((g as u32) << 8) | r as u32));
+    let old = PREVIEW_BRUSH.with(|pb| pb.borrow_mut().replace(brush));
+    if let Some(old) = old {
+        let _ = DeleteObject(old);
+    }
+}
+
+/// Resolve the hex color `ID_COLOR_COMBO` index `idx` represents: one of the
+/// fixed `COLOR_PRESETS`, then one of `config.recent_colors`. Returns `None`
+/// for the trailing `CUSTOM_COLOR_SENTINEL` entry, which names no color.
+fn color_combo_hex_for_index(config: &Config, idx: usize) -> Option<String> {
+    if idx < COLOR_PRESETS.len() {
+        Some(COLOR_PRESETS[idx].1.to_string())
+    } else {
+        config
+            .recent_colors
+            .get(idx - COLOR_PRESETS.len())
+            .cloned()
+    }
+}
+
+/// Rebuild `combo`'s entries as the fixed presets, then `config.recent_colors`,
+/// then the `CUSTOM_COLOR_SENTINEL`, and select whichever entry matches
+/// `selected_hex` (if any).
+unsafe fn populate_color_combo(combo: HWND, config: &Config, selected_hex: &str) {
+    SendMessageW(combo, CB_RESETCONTENT, WPARAM(0), LPARAM(0));
+    for (name, _) in COLOR_PRESETS {
+        SendMessageW(combo, CB_ADDSTRING, WPARAM(0), LPARAM(to_wide(name).as_ptr() as isize));
+    }
+    for hex in &config.recent_colors {
+        SendMessageW(combo, CB_ADDSTRING, WPARAM(0), LPARAM(to_wide(hex).as_ptr() as isize));
+    }
+    SendMessageW(
+        combo,
+        CB_ADDSTRING,
+        WPARAM(0),
+        LPARAM(to_wide(CUSTOM_COLOR_SENTINEL).as_ptr() as isize),
+    );
+
+    let idx = COLOR_PRESETS
+        .iter()
+        .position(|(_, hex)| hex.eq_ignore_ascii_case(selected_hex))
+        .or_else(|| {
+            config
+                .recent_colors
+                .iter()
+                .position(|hex| hex.eq_ignore_ascii_case(selected_hex))
+                .map(|i| i + COLOR_PRESETS.len())
+        });
+    if let Some(idx) = idx {
+        SendMessageW(combo, CB_SETCURSEL, WPARAM(idx), LPARAM(0));
+    }
+}
+
+/// Convert a `#RRGGBB` hex string to the `0x00BBGGRR` packed form `COLORREF`
+/// and `CHOOSECOLORW::lpCustColors` both use.
+fn hex_to_colorref(hex: &str) -> u32 {
+    let (r, g, b) = crate::config::parse_hex_color(hex);
+    ((b as u32) << 16) | ((g as u32) << 8) | r as u32
+}
+
+/// Inverse of `hex_to_colorref`.
+fn colorref_to_hex(colorref: u32) -> String {
+    let r = (colorref & 0xFF) as u8;
+    let g = ((colorref >> 8) & 0xFF) as u8;
+    let b = ((colorref >> 16) & 0xFF) as u8;
+    format!("#{r:02X}{g:02X}{b:02X}")
+}
+
+/// Open the common `ChooseColorW` dialog seeded with `seed_hex` and whatever
+/// custom colors are already in `config.recent_colors`, returning the chosen
+/// `#RRGGBB` hex if the user pressed OK.
+unsafe fn show_custom_color_dialog(hwnd: HWND, seed_hex: &str, config: &Config) -> Option<String> {
+    let mut custom_colors = [hex_to_colorref("#FFFFFF"); 16];
+    for (slot, hex) in custom_colors.iter_mut().zip(config.recent_colors.iter()) {
+        *slot = hex_to_colorref(hex);
+    }
+
+    let mut choose = CHOOSECOLORW {
+        lStructSize: std::mem::size_of::<CHOOSECOLORW>() as u32,
+        hwndOwner: hwnd,
+        rgbResult: COLORREF(hex_to_colorref(seed_hex)),
+        lpCustColors: custom_colors.as_mut_ptr(),
+        Flags: CC_RGBINIT | CC_FULLOPEN,
+        ..Default::default()
+    };
+
+    if ChooseColorW(&mut choose).as_bool() {
+        Some(colorref_to_hex(choose.rgbResult.0))
+    } else {
+        None
+    }
+}
+
+unsafe extern "system" fn preview_wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match msg {
+        WM_PAINT => {
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = BeginPaint(hwnd, &mut ps);
+            let mut rect = RECT::default();
+            let _ = GetClientRect(hwnd, &mut rect);
+            PREVIEW_BRUSH.with(|pb| {
+                if let Some(brush) = *pb.borrow() {
+                    FillRect(hdc, &rect, brush);
+                }
+            });
+            let _ = EndPaint(hwnd, &ps);
+            LRESULT(0)
+        }
+        WM_ERASEBKGND => LRESULT(1),
+        WM_DESTROY => {
+            if let Some(brush) = PREVIEW_BRUSH.with(|pb| pb.borrow_mut().take()) {
+                let _ = DeleteObject(brush);
+            }
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+/// Create the dialog's default UI font, scaled for `dpi`.
+unsafe fn get_default_font(dpi: u32) -> HFONT {
     let ncm = NONCLIENTMETRICSW {
         cbSize: std::mem::size_of::<NONCLIENTMETRICSW>() as u32,
         ..Default::default()
     };
     // Return a reasonable default font
     CreateFontW(
-        -14, 0, 0, 0,
+        scale_for_dpi(-14, dpi), 0, 0, 0,
         FW_NORMAL.0 as i32,
         0, 0, 0,
         DEFAULT_CHARSET.0 as u32,
@@ -368,6 +916,79 @@ fn from_wide(wide: &[u16]) -> String {
     String::from_utf16_lossy(&wide[..len])
 }
 
+/// Convert a `Hotkey` to the `(vk, modifiers)` byte pair that the
+/// `msctls_hotkey32` control's `HKM_SETHOTKEY`/`HKM_GETHOTKEY` messages use.
+/// This is a different modifier encoding (`HOTKEYF_*` bits) than
+/// `Hotkey::to_modifiers_and_vk`'s `MOD_*` bitmask, which targets
+/// `RegisterHotKey` instead. The control has no way to represent the
+/// Windows key, so a configured `win` modifier is silently dropped.
+fn hotkey_to_hkm_bytes(hotkey: crate::config::Hotkey) -> (u8, u8) {
+    let mut modifiers = 0u8;
+    if hotkey.shift {
+        modifiers |= HOTKEYF_SHIFT as u8;
+    }
+    if hotkey.ctrl {
+        modifiers |= HOTKEYF_CONTROL as u8;
+    }
+    if hotkey.alt {
+        modifiers |= HOTKEYF_ALT as u8;
+    }
+    (hotkey.key.to_vk() as u8, modifiers)
+}
+
+/// Inverse of `hotkey_to_hkm_bytes`: decode an `HKM_GETHOTKEY` result's
+/// `(vk, modifiers)` byte pair back into a `Hotkey`. Returns `None` if `vk`
+/// isn't a key this app recognizes (including `0`, meaning no key was ever
+/// pressed in the control).
+fn hkm_bytes_to_hotkey(vk: u8, modifiers: u8) -> Option<crate::config::Hotkey> {
+    Some(crate::config::Hotkey {
+        ctrl: (modifiers & HOTKEYF_CONTROL as u8) != 0,
+        alt: (modifiers & HOTKEYF_ALT as u8) != 0,
+        shift: (modifiers & HOTKEYF_SHIFT as u8) != 0,
+        win: false,
+        key: crate::config::Key::from_vk(vk as u32)?,
+    })
+}
+
+/// Read the current chord out of the `ID_HOTKEY_EDIT` `msctls_hotkey32`
+/// control, decoded into a `Hotkey`.
+unsafe fn read_hotkey_control(hwnd: HWND) -> Option<crate::config::Hotkey> {
+    let hotkey_ctl = find_control(hwnd, ID_HOTKEY_EDIT)?;
+    let result = SendMessageW(hotkey_ctl, HKM_GETHOTKEY, WPARAM(0), LPARAM(0)).0;
+    let vk = (result & 0xFF) as u8;
+    let modifiers = ((result >> 8) & 0xFF) as u8;
+    hkm_bytes_to_hotkey(vk, modifiers)
+}
+
+/// Validate the hotkey control and, if it holds a real chord, flush every
+/// control into `DIALOG_CONFIG` and stash the result in `DIALOG_RESULT`.
+/// Shared by Apply (which leaves the dialog open) and OK (which then closes
+/// it); returns whether validation passed.
+unsafe fn validate_and_commit(hwnd: HWND) -> bool {
+    // HKM_SETRULES already keeps the control from holding an
+    // unregisterable chord, but it can still be empty or hold a key
+    // `Key::from_vk` doesn't recognize - guard against that before
+    // accepting the dialog
+    if read_hotkey_control(hwnd).is_none() {
+        let wide_msg = to_wide("Press a key combination for the hotkey.");
+        let wide_title = to_wide("Invalid Hotkey");
+        MessageBoxW(
+            Some(hwnd),
+            PCWSTR(wide_msg.as_ptr()),
+            PCWSTR(wide_title.as_ptr()),
+            MB_OK | MB_ICONWARNING,
+        );
+        return false;
+    }
+
+    if let Some(config) = gather_dialog_values(hwnd) {
+        DIALOG_RESULT.with(|r| {
+            *r.borrow_mut() = Some(config);
+        });
+    }
+    true
+}
+
 unsafe extern "system" fn settings_wnd_proc(
     hwnd: HWND,
     msg: u32,
@@ -381,36 +1002,287 @@ unsafe extern "system" fn settings_wnd_proc(
 
             match id {
                 ID_OK => {
-                    // Gather values from controls and save config
-                    if let Some(config) = gather_dialog_values(hwnd) {
-                        DIALOG_RESULT.with(|r| {
-                            *r.borrow_mut() = Some(config);
-                        });
+                    if validate_and_commit(hwnd) {
+                        let _ = DestroyWindow(hwnd);
                     }
-                    let _ = DestroyWindow(hwnd);
+                }
+                ID_APPLY => {
+                    validate_and_commit(hwnd);
                 }
                 ID_CANCEL => {
+                    // Discard any config an earlier Apply click already
+                    // stashed in DIALOG_RESULT, so Cancel truly cancels
+                    DIALOG_RESULT.with(|r| {
+                        *r.borrow_mut() = None;
+                    });
                     let _ = DestroyWindow(hwnd);
                 }
+                ID_COLOR_COMBO if notification == CBN_SELCHANGE => {
+                    if let Some(combo) = find_control(hwnd, ID_COLOR_COMBO) {
+                        let idx = SendMessageW(combo, CB_GETCURSEL, WPARAM(0), LPARAM(0)).0 as usize;
+                        let count = SendMessageW(combo, CB_GETCOUNT, WPARAM(0), LPARAM(0)).0 as usize;
+                        let config =
+                            DIALOG_CONFIG.with(|c| c.borrow().clone()).unwrap_or_default();
+
+                        let resolved_hex = if idx + 1 == count {
+                            // Custom... sentinel: open the common color
+                            // picker seeded with whatever this scope
+                            // currently shows, falling back to that same
+                            // color (i.e. no visible change) if cancelled
+                            let seed = current_effective_config().overlay_color;
+                            match show_custom_color_dialog(hwnd, &seed, &config) {
+                                Some(hex) => {
+                                    DIALOG_CONFIG.with(|c| {
+                                        if let Some(cfg) = c.borrow_mut().as_mut() {
+                                            cfg.push_recent_color(&hex);
+                                        }
+                                    });
+                                    hex
+                                }
+                                None => seed,
+                            }
+                        } else {
+                            color_combo_hex_for_index(&config, idx).unwrap_or(config.overlay_color)
+                        };
+
+                        let config =
+                            DIALOG_CONFIG.with(|c| c.borrow().clone()).unwrap_or_default();
+                        populate_color_combo(combo, &config, &resolved_hex);
+
+                        let (r, g, b) = crate::config::parse_hex_color(&resolved_hex);
+                        set_preview_brush(r, g, b);
+                        if let Some(preview) = find_control(hwnd, ID_COLOR_PREVIEW) {
+                            InvalidateRect(preview, None, true);
+                        }
+                    }
+                }
+                ID_PROFILE_COMBO if notification == CBN_SELCHANGE => {
+                    commit_profile_edits(hwnd);
+                    if let Some(combo) = find_control(hwnd, ID_PROFILE_COMBO) {
+                        let idx = SendMessageW(combo, CB_GETCURSEL, WPARAM(0), LPARAM(0)).0;
+                        if idx >= 0 {
+                            let len =
+                                SendMessageW(combo, CB_GETLBTEXTLEN, WPARAM(idx as usize), LPARAM(0)).0;
+                            let mut buffer = vec![0u16; len as usize + 1];
+                            SendMessageW(
+                                combo,
+                                CB_GETLBTEXT,
+                                WPARAM(idx as usize),
+                                LPARAM(buffer.as_mut_ptr() as isize),
+                            );
+                            populate_profile_controls(hwnd, &from_wide(&buffer));
+                        }
+                    }
+                }
+                ID_PROFILE_ADD_BUTTON => {
+                    if let Some(combo) = find_control(hwnd, ID_PROFILE_COMBO) {
+                        let mut buffer = [0u16; 256];
+                        let len = GetWindowTextW(combo, &mut buffer) as usize;
+                        let name = from_wide(&buffer[..len]).trim().to_string();
+                        if !name.is_empty() && !name.eq_ignore_ascii_case(PROFILE_GLOBAL_SENTINEL) {
+                            let existing = SendMessageW(
+                                combo,
+                                CB_FINDSTRINGEXACT,
+                                WPARAM(usize::MAX),
+                                LPARAM(to_wide(&name).as_ptr() as isize),
+                            )
+                            .0;
+                            if existing < 0 {
+                                SendMessageW(
+                                    combo,
+                                    CB_ADDSTRING,
+                                    WPARAM(0),
+                                    LPARAM(to_wide(&name).as_ptr() as isize),
+                                );
+                                DIALOG_CONFIG.with(|c| {
+                                    if let Some(cfg) = c.borrow_mut().as_mut() {
+                                        cfg.profiles.push(crate::config::AppProfile {
+                                            executable: name.clone(),
+                                            ..Default::default()
+                                        });
+                                    }
+                                });
+                            }
+                            let idx = SendMessageW(
+                                combo,
+                                CB_FINDSTRINGEXACT,
+                                WPARAM(usize::MAX),
+                                LPARAM(to_wide(&name).as_ptr() as isize),
+                            )
+                            .0;
+                            if idx >= 0 {
+                                SendMessageW(combo, CB_SETCURSEL, WPARAM(idx as usize), LPARAM(0));
+                            }
+                            populate_profile_controls(hwnd, &name);
+                        }
+                    }
+                }
+                ID_PROFILE_REMOVE_BUTTON => {
+                    if let Some(combo) = find_control(hwnd, ID_PROFILE_COMBO) {
+                        let idx = SendMessageW(combo, CB_GETCURSEL, WPARAM(0), LPARAM(0)).0;
+                        if idx > 0 {
+                            let len =
+                                SendMessageW(combo, CB_GETLBTEXTLEN, WPARAM(idx as usize), LPARAM(0)).0;
+                            let mut buffer = vec![0u16; len as usize + 1];
+                            SendMessageW(
+                                combo,
+                                CB_GETLBTEXT,
+                                WPARAM(idx as usize),
+                                LPARAM(buffer.as_mut_ptr() as isize),
+                            );
+                            let name = from_wide(&buffer);
+
+                            SendMessageW(combo, CB_DELETESTRING, WPARAM(idx as usize), LPARAM(0));
+                            DIALOG_CONFIG.with(|c| {
+                                if let Some(cfg) = c.borrow_mut().as_mut() {
+                                    cfg.profiles.retain(|p| p.executable != name);
+                                }
+                            });
+                            SendMessageW(combo, CB_SETCURSEL, WPARAM(0), LPARAM(0));
+                            populate_profile_controls(hwnd, PROFILE_GLOBAL_SENTINEL);
+                        }
+                    }
+                }
+                ID_LEARN_DEVICE_BUTTON => {
+                    crate::raw_input::begin_learn_device();
+
+                    // The message-only Raw Input window runs its own message
+                    // loop on its own thread, so it still receives WM_INPUT
+                    // while this modal message box is showing.
+                    let wide_msg =
+                        to_wide("Press a key, or click/move the trusted device now, then press OK.");
+                    let wide_title = to_wide("Learn Device");
+                    MessageBoxW(
+                        Some(hwnd),
+                        PCWSTR(wide_msg.as_ptr()),
+                        PCWSTR(wide_title.as_ptr()),
+                        MB_OK | MB_ICONINFORMATION,
+                    );
+
+                    match crate::raw_input::take_learned_device() {
+                        Some(id) => {
+                            if let Some(list) = find_control(hwnd, ID_TRUSTED_DEVICES_LIST) {
+                                SendMessageW(
+                                    list,
+                                    LB_ADDSTRING,
+                                    WPARAM(0),
+                                    LPARAM(to_wide(&id).as_ptr() as isize),
+                                );
+                            }
+                        }
+                        None => {
+                            let wide_msg = to_wide("No input was detected. Try again.");
+                            let wide_title = to_wide("Learn Device");
+                            MessageBoxW(
+                                Some(hwnd),
+                                PCWSTR(wide_msg.as_ptr()),
+                                PCWSTR(wide_title.as_ptr()),
+                                MB_OK | MB_ICONWARNING,
+                            );
+                        }
+                    }
+                }
+                ID_REMOVE_DEVICE_BUTTON => {
+                    if let Some(list) = find_control(hwnd, ID_TRUSTED_DEVICES_LIST) {
+                        let sel = SendMessageW(list, LB_GETCURSEL, WPARAM(0), LPARAM(0)).0;
+                        if sel >= 0 {
+                            SendMessageW(list, LB_DELETESTRING, WPARAM(sel as usize), LPARAM(0));
+                        }
+                    }
+                }
                 _ => {}
             }
             LRESULT(0)
         }
 
+        WM_NOTIFY => {
+            let nmhdr = &*(lparam.0 as *const NMHDR);
+            if nmhdr.idFrom as i32 == ID_TAB_CONTROL && nmhdr.code as i32 == TCN_SELCHANGE {
+                // Flush whatever the General page currently shows before
+                // hiding it, same as switching profiles
+                commit_profile_edits(hwnd);
+                show_active_tab_page(hwnd);
+                if let Some(tab) = find_control(hwnd, ID_TAB_CONTROL) {
+                    let selected = SendMessageW(tab, TCM_GETCURSEL, WPARAM(0), LPARAM(0)).0 as i32;
+                    ACTIVE_TAB.with(|t| *t.borrow_mut() = Some(selected));
+                }
+            }
+            LRESULT(0)
+        }
+
         WM_HSCROLL => {
             // Handle slider changes
-            let slider = GetDlgItem(hwnd, ID_OPACITY_SLIDER);
+            let slider = find_control(hwnd, ID_OPACITY_SLIDER);
             if lparam.0 == slider.unwrap_or(HWND(std::ptr::null_mut())).0 as isize {
                 let pos = SendMessageW(slider.unwrap(), TBM_GETPOS, WPARAM(0), LPARAM(0)).0 as i32;
-                let label = GetDlgItem(hwnd, ID_OPACITY_LABEL);
+                let label = find_control(hwnd, ID_OPACITY_LABEL);
                 if let Some(lbl) = label {
                     let text = format!("{}%", pos);
                     SetWindowTextW(lbl, PCWSTR(to_wide(&text).as_ptr()));
                 }
+                if let Some(preview) = find_control(hwnd, ID_COLOR_PREVIEW) {
+                    let alpha = (pos.clamp(0, 100) * 255 / 100) as u8;
+                    let _ = SetLayeredWindowAttributes(preview, COLORREF(0), alpha, LWA_ALPHA);
+                }
             }
             LRESULT(0)
         }
 
+        WM_DPICHANGED => {
+            // `lparam` points at the suggested window rect for the new DPI;
+            // move/resize the dialog there first so `create_dialog_controls`
+            // (which reads the DPI back via `GetDpiForWindow`) sees the new
+            // scale when it rebuilds the layout below.
+            let suggested = &*(lparam.0 as *const RECT);
+            let _ = SetWindowPos(
+                hwnd,
+                None,
+                suggested.left,
+                suggested.top,
+                suggested.right - suggested.left,
+                suggested.bottom - suggested.top,
+                SWP_NOZORDER | SWP_NOACTIVATE,
+            );
+
+            // Tear down every control and recreate them at the new scale -
+            // simpler and just as correct as repositioning each one by hand,
+            // since create_dialog_controls already knows the full layout
+            for child in enumerate_children(hwnd) {
+                let _ = DestroyWindow(child);
+            }
+            if let Some(config) = DIALOG_CONFIG.with(|c| c.borrow().clone()) {
+                create_dialog_controls(hwnd, &config);
+
+                // Re-select whichever profile scope was showing before the
+                // rebuild, since the fresh profile combo defaults to Global
+                let selection = PROFILE_SELECTION.with(|s| s.borrow().clone());
+                if let Some(combo) = find_control(hwnd, ID_PROFILE_COMBO) {
+                    let idx = SendMessageW(
+                        combo,
+                        CB_FINDSTRINGEXACT,
+                        WPARAM(usize::MAX),
+                        LPARAM(to_wide(&selection).as_ptr() as isize),
+                    )
+                    .0;
+                    if idx >= 0 {
+                        SendMessageW(combo, CB_SETCURSEL, WPARAM(idx as usize), LPARAM(0));
+                    }
+                }
+                populate_profile_controls(hwnd, &selection);
+
+                // Re-select whichever tab was active before the rebuild,
+                // since the fresh tab control defaults to the first one
+                if let Some(active_tab) = ACTIVE_TAB.with(|t| *t.borrow()) {
+                    if let Some(tab) = find_control(hwnd, ID_TAB_CONTROL) {
+                        SendMessageW(tab, TCM_SETCURSEL, WPARAM(active_tab as usize), LPARAM(0));
+                    }
+                }
+                show_active_tab_page(hwnd);
+            }
+
+            LRESULT(0)
+        }
+
         WM_CLOSE => {
             let _ = DestroyWindow(hwnd);
             LRESULT(0)
@@ -425,35 +1297,246 @@ unsafe extern "system" fn settings_wnd_proc(
     }
 }
 
-unsafe fn gather_dialog_values(hwnd: HWND) -> Option<Config> {
-    DIALOG_CONFIG.with(|c| {
-        let mut config = c.borrow().clone()?;
+/// Collect every descendant window of `hwnd` (`EnumChildWindows` also walks
+/// grandchildren such as the controls inside each tab page), used to tear down all dialog
+/// controls before recreating them at a new DPI scale.
+unsafe fn enumerate_children(hwnd: HWND) -> Vec<HWND> {
+    unsafe extern "system" fn collect_proc(child: HWND, lparam: LPARAM) -> BOOL {
+        let children = &mut *(lparam.0 as *mut Vec<HWND>);
+        children.push(child);
+        BOOL(1)
+    }
+
+    let mut children: Vec<HWND> = Vec::new();
+    let _ = EnumChildWindows(
+        Some(hwnd),
+        Some(collect_proc),
+        LPARAM(&mut children as *mut _ as isize),
+    );
+    children
+}
+
+/// Like `GetDlgItem`, but searches the full descendant tree rather than only
+/// immediate children - needed since every dialog control now lives inside a
+/// per-tab page container rather than directly under the dialog.
+unsafe fn find_control(root: HWND, id: i32) -> Option<HWND> {
+    struct Search {
+        id: i32,
+        found: Option<HWND>,
+    }
 
-        // Get hotkey
-        if let Some(edit) = GetDlgItem(hwnd, ID_HOTKEY_EDIT) {
-            let mut buffer = [0u16; 256];
-            let len = GetWindowTextW(edit, &mut buffer) as usize;
-            config.hotkey = from_wide(&buffer[..len]);
+    unsafe extern "system" fn search_proc(child: HWND, lparam: LPARAM) -> BOOL {
+        let search = &mut *(lparam.0 as *mut Search);
+        if GetDlgCtrlID(child) == search.id {
+            search.found = Some(child);
+            return BOOL(0);
         }
+        BOOL(1)
+    }
 
-        // Get opacity from slider
-        if let Some(slider) = GetDlgItem(hwnd, ID_OPACITY_SLIDER) {
-            let pos = SendMessageW(slider, TBM_GETPOS, WPARAM(0), LPARAM(0)).0 as f32;
-            config.opacity = pos / 100.0;
+    let mut search = Search { id, found: None };
+    let _ = EnumChildWindows(
+        Some(root),
+        Some(search_proc),
+        LPARAM(&mut search as *mut _ as isize),
+    );
+    search.found
+}
+
+/// Resolve the effective config for whichever scope `PROFILE_SELECTION`
+/// currently names - the global `DIALOG_CONFIG`, or its `effective_for` a
+/// specific profile's executable. Used to seed the custom color picker with
+/// whatever color the visible scope is currently showing.
+unsafe fn current_effective_config() -> Config {
+    let target = PROFILE_SELECTION.with(|s| s.borrow().clone());
+    let config = DIALOG_CONFIG.with(|c| c.borrow().clone()).unwrap_or_default();
+    if target == PROFILE_GLOBAL_SENTINEL {
+        config
+    } else {
+        config.effective_for(&target)
+    }
+}
+
+/// Read `hotkey`/`opacity`/`overlay_color`/`notifications_enabled` off their
+/// controls and write them into whichever scope `PROFILE_SELECTION` names:
+/// the global `Config` fields, or the matching `AppProfile`'s overrides. A
+/// profile field is only stored as `Some(..)` override when the control's
+/// value differs from the current global value; otherwise it's written as
+/// `None` so the profile keeps inheriting (see `Config::effective_for`).
+/// Called before switching the profile selector so edits aren't lost, and
+/// once more before the dialog closes to flush whatever's still showing.
+unsafe fn commit_profile_edits(hwnd: HWND) {
+    let loaded_hotkey_str = LOADED_HOTKEY.with(|h| h.borrow().clone());
+    let hotkey = read_hotkey_control(hwnd).map(|control_hotkey| {
+        // The control can't represent Win, so compare against the loaded
+        // hotkey with Win stripped rather than the control's raw value -
+        // if they still match, the user never touched the control and we
+        // keep the original string (Win and all) instead of clobbering it.
+        let loaded_hotkey = loaded_hotkey_str
+            .as_deref()
+            .and_then(|l| l.parse::<crate::config::Hotkey>().ok());
+        match loaded_hotkey {
+            Some(mut loaded) if loaded.win => {
+                loaded.win = false;
+                if loaded == control_hotkey {
+                    loaded_hotkey_str.clone().unwrap()
+                } else {
+                    control_hotkey.to_string()
+                }
+            }
+            _ => control_hotkey.to_string(),
         }
+    });
+    let opacity = find_control(hwnd, ID_OPACITY_SLIDER)
+        .map(|slider| SendMessageW(slider, TBM_GETPOS, WPARAM(0), LPARAM(0)).0 as f32 / 100.0);
+    let overlay_color = find_control(hwnd, ID_COLOR_COMBO).and_then(|combo| {
+        let idx = SendMessageW(combo, CB_GETCURSEL, WPARAM(0), LPARAM(0)).0 as usize;
+        DIALOG_CONFIG.with(|c| {
+            c.borrow()
+                .as_ref()
+                .and_then(|cfg| color_combo_hex_for_index(cfg, idx))
+        })
+    });
+    let notifications_enabled = find_control(hwnd, ID_NOTIFICATIONS_CHECK)
+        .map(|check| SendMessageW(check, BM_GETCHECK, WPARAM(0), LPARAM(0)).0 == BST_CHECKED.0 as isize);
 
-        // Get color from combo
-        if let Some(combo) = GetDlgItem(hwnd, ID_COLOR_COMBO) {
-            let idx = SendMessageW(combo, CB_GETCURSEL, WPARAM(0), LPARAM(0)).0 as usize;
-            if idx < COLOR_PRESETS.len() {
-                config.overlay_color = COLOR_PRESETS[idx].1.to_string();
+    let target = PROFILE_SELECTION.with(|s| s.borrow().clone());
+
+    DIALOG_CONFIG.with(|c| {
+        if let Some(cfg) = c.borrow_mut().as_mut() {
+            if target == PROFILE_GLOBAL_SENTINEL {
+                if let Some(h) = hotkey {
+                    cfg.hotkey = h;
+                }
+                if let Some(o) = opacity {
+                    cfg.opacity = o;
+                }
+                if let Some(c) = overlay_color {
+                    cfg.overlay_color = c;
+                }
+                if let Some(n) = notifications_enabled {
+                    cfg.notifications_enabled = n;
+                }
+            } else {
+                // Only persist a field as an explicit override when it
+                // differs from the global value it would otherwise inherit;
+                // a control left matching the global default is stored as
+                // `None` so the profile keeps inheriting, including a global
+                // edit made after this profile was last saved.
+                let global_hotkey = cfg.hotkey.clone();
+                let global_opacity = cfg.opacity;
+                let global_overlay_color = cfg.overlay_color.clone();
+                let global_notifications_enabled = cfg.notifications_enabled;
+                if let Some(profile) = cfg.profiles.iter_mut().find(|p| p.executable == target) {
+                    // Compare parsed chords, not strings, so "ctrl+b" and
+                    // the control's "Ctrl+B" count as the same hotkey
+                    let global_parsed = global_hotkey.parse::<crate::config::Hotkey>().ok();
+                    profile.hotkey =
+                        hotkey.filter(|h| h.parse::<crate::config::Hotkey>().ok() != global_parsed);
+                    profile.opacity = opacity.filter(|o| *o != global_opacity);
+                    profile.overlay_color = overlay_color.filter(|c| *c != global_overlay_color);
+                    profile.notifications_enabled =
+                        notifications_enabled.filter(|n| *n != global_notifications_enabled);
+                }
             }
         }
+    });
+}
+
+/// Populate the hotkey/opacity/color/notifications controls (and the color
+/// preview swatch) from the effective config for `name` (`PROFILE_GLOBAL_SENTINEL`
+/// for the global defaults, or a profile's executable name), and record it
+/// as the active `PROFILE_SELECTION`.
+unsafe fn populate_profile_controls(hwnd: HWND, name: &str) {
+    let Some(config) = DIALOG_CONFIG.with(|c| c.borrow().clone()) else {
+        return;
+    };
+    let effective = if name == PROFILE_GLOBAL_SENTINEL {
+        config
+    } else {
+        config.effective_for(name)
+    };
+
+    if let Some(hotkey_ctl) = find_control(hwnd, ID_HOTKEY_EDIT) {
+        if let Ok(hotkey) = effective.hotkey.parse::<crate::config::Hotkey>() {
+            let (vk, modifiers) = hotkey_to_hkm_bytes(hotkey);
+            SendMessageW(
+                hotkey_ctl,
+                HKM_SETHOTKEY,
+                WPARAM(((modifiers as usize) << 8) | vk as usize),
+                LPARAM(0),
+            );
+        }
+    }
+    LOADED_HOTKEY.with(|h| *h.borrow_mut() = Some(effective.hotkey.clone()));
+    if let Some(slider) = find_control(hwnd, ID_OPACITY_SLIDER) {
+        SendMessageW(
+            slider,
+            TBM_SETPOS,
+            WPARAM(1),
+            LPARAM((effective.opacity * 100.0) as isize),
+        );
+    }
+    if let Some(label) = find_control(hwnd, ID_OPACITY_LABEL) {
+        let text = format!("{}%", (effective.opacity * 100.0) as i32);
+        let _ = SetWindowTextW(label, PCWSTR(to_wide(&text).as_ptr()));
+    }
+    if let Some(combo) = find_control(hwnd, ID_COLOR_COMBO) {
+        populate_color_combo(combo, &effective, &effective.overlay_color);
+    }
+    if let Some(check) = find_control(hwnd, ID_NOTIFICATIONS_CHECK) {
+        let check_state = if effective.notifications_enabled {
+            BST_CHECKED.0 as usize
+        } else {
+            BST_UNCHECKED.0 as usize
+        };
+        SendMessageW(check, BM_SETCHECK, WPARAM(check_state), LPARAM(0));
+    }
 
-        // Get notifications checkbox
-        if let Some(check) = GetDlgItem(hwnd, ID_NOTIFICATIONS_CHECK) {
+    let (r, g, b) = crate::config::parse_hex_color(&effective.overlay_color);
+    set_preview_brush(r, g, b);
+    if let Some(preview) = find_control(hwnd, ID_COLOR_PREVIEW) {
+        let alpha = (effective.opacity * 255.0) as u8;
+        let _ = SetLayeredWindowAttributes(preview, COLORREF(0), alpha, LWA_ALPHA);
+        InvalidateRect(preview, None, true);
+    }
+
+    PROFILE_SELECTION.with(|s| *s.borrow_mut() = name.to_string());
+}
+
+unsafe fn gather_dialog_values(hwnd: HWND) -> Option<Config> {
+    // Flush whatever the hotkey/opacity/color/notifications controls
+    // currently show into the profile (or global config) they belong to
+    commit_profile_edits(hwnd);
+
+    DIALOG_CONFIG.with(|c| {
+        let mut config = c.borrow().clone()?;
+
+        // Get lock-mouse checkbox
+        if let Some(check) = find_control(hwnd, ID_LOCK_MOUSE_CHECK) {
             let state = SendMessageW(check, BM_GETCHECK, WPARAM(0), LPARAM(0)).0;
-            config.notifications_enabled = state == BST_CHECKED.0 as isize;
+            config.lock_mouse = state == BST_CHECKED.0 as isize;
+        }
+
+        // Get trusted devices from the listbox
+        if let Some(list) = find_control(hwnd, ID_TRUSTED_DEVICES_LIST) {
+            let count = SendMessageW(list, LB_GETCOUNT, WPARAM(0), LPARAM(0)).0;
+            let mut ids = Vec::new();
+            for i in 0..count {
+                let len = SendMessageW(list, LB_GETTEXTLEN, WPARAM(i as usize), LPARAM(0)).0;
+                if len < 0 {
+                    continue;
+                }
+                let mut buffer = vec![0u16; len as usize + 1];
+                SendMessageW(
+                    list,
+                    LB_GETTEXT,
+                    WPARAM(i as usize),
+                    LPARAM(buffer.as_mut_ptr() as isize),
+                );
+                ids.push(from_wide(&buffer));
+            }
+            config.allowed_device_ids = ids;
         }
 
         Some(config)
@@ -3,20 +3,37 @@
 //! Provides a system tray icon with a context menu for settings, lock toggle, and exit.
 
 use crate::config::Config;
-use crate::overlay::{create_overlay, destroy_overlay, set_overlay_visible};
+use crate::overlay::{create_overlay, destroy_overlay, rebuild_overlay, set_overlay_visible};
+use crate::raw_input;
 use crate::settings_dialog;
 use crate::AppState;
 use log::info;
-use muda::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use muda::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem};
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use tray_icon::{Icon, TrayIcon, TrayIconBuilder};
+use tray_icon::{ClickType, Icon, TrayIcon, TrayIconBuilder, TrayIconEvent};
 use windows::Win32::Foundation::HWND;
 use windows::Win32::UI::WindowsAndMessaging::*;
 
+/// Format the tray tooltip from the configured hotkey, e.g. "PawGate - Ctrl+B to lock"
+fn tray_tooltip(config: &Config) -> String {
+    match config.hotkey.parse::<crate::config::Hotkey>() {
+        Ok(hotkey) => format!("PawGate - {hotkey} to lock"),
+        Err(_) => "PawGate - Keyboard Locker".to_string(),
+    }
+}
+
+/// The subset of `Config` that changes what the overlay windows look like,
+/// used to tell whether a profile switch needs `rebuild_overlay` or just a
+/// hotkey reload.
+fn overlay_appearance(config: &Config) -> (u32, String, bool) {
+    ((config.opacity * 255.0) as u32, config.overlay_color.clone(), config.lock_mouse)
+}
+
 /// Menu item IDs
 const MENU_LOCK: &str = "lock";
 const MENU_SETTINGS: &str = "settings";
+const MENU_NOTIFICATIONS: &str = "notifications";
 const MENU_EXIT: &str = "exit";
 
 /// Create the tray icon from embedded or generated icon
@@ -92,32 +109,54 @@ pub fn run_tray_loop(state: Arc<AppState>, mut config: Config) -> Result<(), Box
 
     let lock_item = MenuItem::with_id(MENU_LOCK, "Lock Keyboard", true, None);
     let settings_item = MenuItem::with_id(MENU_SETTINGS, "Settings...", true, None);
+    let notifications_item = CheckMenuItem::with_id(
+        MENU_NOTIFICATIONS,
+        "Enable notifications",
+        true,
+        config.notifications_enabled,
+        None,
+    );
     let exit_item = MenuItem::with_id(MENU_EXIT, "Exit", true, None);
 
     menu.append(&lock_item)?;
     menu.append(&PredefinedMenuItem::separator())?;
     menu.append(&settings_item)?;
+    menu.append(&notifications_item)?;
     menu.append(&PredefinedMenuItem::separator())?;
     menu.append(&exit_item)?;
 
-    // Create tray icon
+    // Create tray icon, with the tooltip showing the current unlock hotkey
+    // in its canonical "Ctrl+Shift+L" form
     let icon = create_tray_icon();
-    let _tray_icon = TrayIconBuilder::new()
+    let tray_icon = TrayIconBuilder::new()
         .with_menu(Box::new(menu))
-        .with_tooltip("PawGate - Keyboard Locker")
+        .with_tooltip(tray_tooltip(&config))
         .with_icon(icon)
         .build()?;
 
     info!("Tray icon created");
 
-    // Create overlay window (initially hidden)
-    let overlay_hwnd = create_overlay(Arc::clone(&state), &config);
+    // Raw Input device identification (trusted-device allowlist) runs on
+    // its own thread, started alongside the keyboard/mouse hooks in `main`.
+
+    // Create overlay windows, one per monitor (initially hidden)
+    let mut overlay = create_overlay(Arc::clone(&state), &config);
 
     // Track previous lock state to detect changes
     let mut prev_locked = false;
 
+    // Name of the per-application profile (if any) currently driving the
+    // effective hotkey/overlay appearance, so we only reapply on change
+    let mut active_profile: Option<String> = None;
+
+    // (opacity, overlay_color, lock_mouse) of whichever config last drove the
+    // overlay windows, so switching between two profiles with identical
+    // overlay appearance doesn't tear down and recreate every monitor window
+    let mut active_overlay_appearance = overlay_appearance(&config);
+
     // Main event loop
     let menu_receiver = MenuEvent::receiver();
+    let tray_receiver = TrayIconEvent::receiver();
 
     loop {
         // Check for quit signal
@@ -143,11 +182,29 @@ pub fn run_tray_loop(state: Arc<AppState>, mut config: Config) -> Result<(), Box
                         } else {
                             config = new_config;
                             info!("Settings saved");
-                            // Note: Hotkey changes require restart to take effect
-                            // We could signal the keyboard thread to reload, but simpler to restart
+                            let _ = tray_icon.set_tooltip(Some(tray_tooltip(&config)));
+                            notifications_item.set_checked(config.notifications_enabled);
+                            raw_input::update_allowlist(&state, config.allowed_device_ids.clone());
+                            crate::keyboard::reload_hotkey(
+                                &state,
+                                &config.hotkey,
+                                config.hotkey_match_physical,
+                            );
+                            // Clear the active profile so the profile check
+                            // below reapplies on the next tick if a profile's
+                            // executable is in the foreground, in case its
+                            // overrides changed
+                            active_profile = None;
                         }
                     }
                 }
+                MENU_NOTIFICATIONS => {
+                    config.notifications_enabled = notifications_item.is_checked();
+                    info!("Notifications {}", if config.notifications_enabled { "enabled" } else { "disabled" });
+                    if let Err(e) = config.save() {
+                        log::error!("Failed to save config: {}", e);
+                    }
+                }
                 MENU_EXIT => {
                     info!("Exit requested");
                     state.should_quit.store(true, Ordering::SeqCst);
@@ -157,11 +214,72 @@ pub fn run_tray_loop(state: Arc<AppState>, mut config: Config) -> Result<(), Box
             }
         }
 
+        // Double-clicking the tray icon locks immediately, without having
+        // to open the menu
+        if let Ok(event) = tray_receiver.try_recv() {
+            if event.click_type == ClickType::Double {
+                info!("Tray icon double-clicked, locking");
+                state.locked.store(true, Ordering::SeqCst);
+            }
+        }
+
+        // Auto-lock after the configured idle period, if enabled
+        if let Some(auto_lock_seconds) = config.auto_lock_seconds {
+            let idle_ms = crate::current_tick()
+                .saturating_sub(state.last_input_tick.load(Ordering::SeqCst));
+            if idle_ms >= auto_lock_seconds as u64 * 1000 && !state.locked.load(Ordering::SeqCst) {
+                info!("Idle for {}s, auto-locking", auto_lock_seconds);
+                state.locked.store(true, Ordering::SeqCst);
+            }
+        }
+
+        // Apply per-application lock profile overrides based on the
+        // foreground window, falling back to the global config otherwise.
+        // Skipped entirely when there are no profiles configured (the
+        // default), since querying the foreground window's owning process
+        // is otherwise pure overhead on every ~16ms tick.
+        if !config.profiles.is_empty() {
+            let foreground_profile = crate::foreground_executable_name().and_then(|exe| {
+                config
+                    .profiles
+                    .iter()
+                    .find(|p| p.executable.eq_ignore_ascii_case(&exe))
+                    .map(|p| p.executable.clone())
+            });
+            if foreground_profile != active_profile {
+                let effective = match &foreground_profile {
+                    Some(exe) => config.effective_for(exe),
+                    None => config.clone(),
+                };
+                info!("Active lock profile: {:?}", foreground_profile);
+                crate::keyboard::reload_hotkey(&state, &effective.hotkey, config.hotkey_match_physical);
+
+                // Only tear down and recreate the per-monitor windows if the
+                // new profile actually changed how the overlay looks
+                let new_appearance = overlay_appearance(&effective);
+                if new_appearance != active_overlay_appearance {
+                    if let Some(old) = overlay.take() {
+                        overlay = rebuild_overlay(old, Arc::clone(&state), &effective);
+                    }
+                    active_overlay_appearance = new_appearance;
+                }
+                active_profile = foreground_profile;
+            }
+        }
+
+        // Rebuild the overlay windows if a monitor was hot-plugged or rescaled
+        if state.monitors_changed.swap(false, Ordering::SeqCst) {
+            info!("Display configuration changed, rebuilding overlay windows");
+            if let Some(old) = overlay.take() {
+                overlay = rebuild_overlay(old, Arc::clone(&state), &config);
+            }
+        }
+
         // Check for lock state changes
         let current_locked = state.locked.load(Ordering::SeqCst);
         if current_locked != prev_locked {
-            if let Some(hwnd) = overlay_hwnd {
-                set_overlay_visible(hwnd, current_locked);
+            if let Some(ref o) = overlay {
+                set_overlay_visible(o, current_locked);
 
                 // Update menu item text
                 let new_text = if current_locked {
@@ -192,8 +310,8 @@ pub fn run_tray_loop(state: Arc<AppState>, mut config: Config) -> Result<(), Box
     }
 
     // Cleanup
-    if let Some(hwnd) = overlay_hwnd {
-        destroy_overlay(hwnd);
+    if let Some(o) = overlay {
+        destroy_overlay(&o);
     }
 
     Ok(())